@@ -10,7 +10,7 @@ use gooey::{
     widgets::component::{Behavior, Component, ComponentCommand},
     App,
 };
-use gooey_canvas::{AppExt, Canvas, CanvasRenderer, Command};
+use gooey_canvas::{AccessibilityTree, AppExt, Canvas, CanvasRenderer, Command};
 
 #[cfg(all(test, not(target_arch = "wasm32")))]
 mod harness;
@@ -69,6 +69,9 @@ impl Behavior for Basic {
                     renderer.draw_image(&image, Point::<f32, Scaled>::new(128., 128.));
                 },
             )
+            .with_accessibility(AccessibilityTree::new(
+                "A dark blue rectangle with an image in its corner",
+            ))
             .finish()
     }
 
@@ -95,18 +98,20 @@ mod tests {
     async fn demo() -> anyhow::Result<()> {
         for theme in [SystemTheme::Dark, SystemTheme::Light] {
             let headless = app().headless();
-            let snapshot = headless
+            let screenshot = headless
                 .screenshot(Size::new(320, 240), theme, None)
-                .await?
-                .to_rgb8();
+                .await?;
+            let snapshot = screenshot.to_rgb8();
 
             assert_ne!(snapshot.get_pixel(160, 120), snapshot.get_pixel(1, 1));
             assert_eq!(snapshot.get_pixel(160, 120).0, [255_u8, 0, 0]);
 
-            snapshot.save(harness::snapshot_path(
+            harness::assert_snapshot(
                 "basic",
                 &format!("Demo-{:?}.png", theme),
-            )?)?;
+                &screenshot.to_rgba8(),
+                harness::Tolerance::default(),
+            )?;
         }
         Ok(())
     }
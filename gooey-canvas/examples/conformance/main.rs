@@ -0,0 +1,84 @@
+//! Draws the reference scenes used to check that every backend renders the
+//! same way. Run with the name of a scene to view it, for example:
+//!
+//! ```sh
+//! cargo run --example conformance -- nested-clips
+//! ```
+
+use gooey::App;
+use gooey_canvas::{AppExt, Canvas};
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+#[path = "../harness/mod.rs"]
+mod harness;
+mod scenes;
+
+use scenes::{ReferenceScene, SceneCanvas, SCENES};
+
+fn app(scene: &'static ReferenceScene) -> App {
+    App::from_root(move |_storage| Canvas::new(SceneCanvas::new(scene))).with_canvas()
+}
+
+fn main() {
+    let name = std::env::args().nth(1);
+    let scene = SCENES
+        .iter()
+        .find(|scene| Some(scene.name) == name.as_deref())
+        .unwrap_or(&SCENES[0]);
+    app(scene).run()
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use gooey::core::{figures::Size, styles::SystemTheme};
+
+    use super::*;
+    use crate::{
+        harness::Tolerance,
+        scenes::{assets_loaded, MISMATCH_TOLERANCE, SCENE_HEIGHT, SCENE_WIDTH},
+    };
+
+    /// How long to wait for a scene's assets to load.
+    const ASSET_TIMEOUT: Duration = Duration::from_secs(10);
+
+    #[tokio::test]
+    async fn conformance() -> anyhow::Result<()> {
+        let tolerance = Tolerance {
+            mismatched: MISMATCH_TOLERANCE,
+            ..Tolerance::default()
+        };
+        let mut failures = Vec::new();
+        for scene in SCENES {
+            let headless = app(scene).headless();
+            let size = Size::new(SCENE_WIDTH, SCENE_HEIGHT);
+            let mut snapshot = headless.screenshot(size, SystemTheme::Light, None).await?;
+            if scene.uses_assets {
+                // The first frame starts loading the assets. Once they've
+                // loaded, the scene is drawn again with them.
+                let started = Instant::now();
+                while !assets_loaded() {
+                    anyhow::ensure!(
+                        started.elapsed() < ASSET_TIMEOUT,
+                        "{}: the assets didn't load",
+                        scene.name
+                    );
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+                snapshot = headless.screenshot(size, SystemTheme::Light, None).await?;
+            }
+            let snapshot = snapshot.to_rgba8();
+            if let Err(err) = harness::assert_snapshot(
+                "conformance",
+                &format!("{}.png", scene.name),
+                &snapshot,
+                tolerance,
+            ) {
+                failures.push(err.to_string());
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+        Ok(())
+    }
+}
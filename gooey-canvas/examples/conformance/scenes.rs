@@ -0,0 +1,379 @@
+//! Reference scenes drawn identically by every backend.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gooey::{
+    core::{
+        assets::{Asset, Image},
+        figures::{Figure, Point, Rect, Size, SizedRect, Vector},
+        styles::Color,
+        Callback, Context, Pixels, Scaled,
+    },
+    frontends::rasterizer::ContentArea,
+    renderer::{Renderer, StrokeOptions, TextOptions},
+};
+use gooey_canvas::{
+    AccessibilityTree, Canvas, CanvasRenderer, Command, LineCap, Path, Renderable, StrokeStyle,
+    Texture, Transform,
+};
+
+/// The width every scene is drawn at, in pixels.
+pub const SCENE_WIDTH: u32 = 320;
+/// The height every scene is drawn at, in pixels.
+pub const SCENE_HEIGHT: u32 = 240;
+
+/// The fraction of pixels that may differ from the golden image. Text is
+/// rasterized slightly differently by each backend.
+#[allow(dead_code)]
+pub const MISMATCH_TOLERANCE: f32 = 0.01;
+
+/// Set once the assets used by the scenes have loaded.
+static ASSETS_LOADED: AtomicBool = AtomicBool::new(false);
+
+/// Returns true once the assets used by the scenes that
+/// [use assets](ReferenceScene::uses_assets) have loaded. Scenes drawn before
+/// then are missing their images.
+#[allow(dead_code)]
+pub fn assets_loaded() -> bool {
+    ASSETS_LOADED.load(Ordering::SeqCst)
+}
+
+/// A named scene that exercises part of the [`Renderer`] API.
+pub struct ReferenceScene {
+    pub name: &'static str,
+    pub renderable: fn() -> Box<dyn Renderable>,
+    /// True if the scene loads assets, which aren't available to the browser
+    /// test runner.
+    #[allow(dead_code)]
+    pub uses_assets: bool,
+}
+
+pub const SCENES: &[ReferenceScene] = &[
+    ReferenceScene {
+        name: "strokes",
+        renderable: || Box::new(strokes),
+        uses_assets: false,
+    },
+    ReferenceScene {
+        name: "clipping",
+        renderable: || Box::new(clipping),
+        uses_assets: false,
+    },
+    ReferenceScene {
+        name: "text-baselines",
+        renderable: || Box::new(text_baselines),
+        uses_assets: false,
+    },
+    ReferenceScene {
+        name: "nested-clips",
+        renderable: || Box::new(nested_clips),
+        uses_assets: false,
+    },
+    ReferenceScene {
+        name: "shaped-clips",
+        renderable: || Box::new(shaped_clips),
+        uses_assets: false,
+    },
+    ReferenceScene {
+        name: "textures",
+        renderable: || Box::new(Textures::default()),
+        uses_assets: false,
+    },
+    ReferenceScene {
+        name: "dashes",
+        renderable: || Box::new(dashes),
+        uses_assets: false,
+    },
+    ReferenceScene {
+        name: "pixel-snapping",
+        renderable: || Box::new(pixel_snapping),
+        uses_assets: false,
+    },
+    ReferenceScene {
+        name: "images",
+        renderable: || Box::new(Images::default()),
+        uses_assets: true,
+    },
+];
+
+/// Forwards to a boxed scene, and optionally hands the canvas' context to
+/// `on_initialize` so that the test can send it commands.
+pub struct SceneCanvas {
+    scene: Box<dyn Renderable>,
+    on_initialize: Option<Box<dyn FnOnce(&Context<Canvas>) + Send + Sync>>,
+}
+
+impl SceneCanvas {
+    pub fn new(scene: &ReferenceScene) -> Self {
+        Self {
+            scene: (scene.renderable)(),
+            on_initialize: None,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn on_initialize<F: FnOnce(&Context<Canvas>) + Send + Sync + 'static>(
+        mut self,
+        on_initialize: F,
+    ) -> Self {
+        self.on_initialize = Some(Box::new(on_initialize));
+        self
+    }
+}
+
+impl Renderable for SceneCanvas {
+    fn initialize(&mut self, context: &Context<Canvas>) {
+        self.scene.initialize(context);
+        if let Some(on_initialize) = self.on_initialize.take() {
+            on_initialize(context);
+        }
+    }
+
+    fn render(&mut self, renderer: CanvasRenderer, content_area: &ContentArea) {
+        self.scene.render(renderer, content_area);
+    }
+
+    fn accessibility(&self) -> Option<AccessibilityTree> {
+        self.scene.accessibility()
+    }
+}
+
+fn scene_bounds() -> Rect<f32, Scaled> {
+    Rect::from(SizedRect::new(
+        Point::default(),
+        Size::new(SCENE_WIDTH as f32, SCENE_HEIGHT as f32),
+    ))
+}
+
+fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect<f32, Scaled> {
+    Rect::from(SizedRect::new(Point::new(x, y), Size::new(width, height)))
+}
+
+fn stroke(color: Color, width: f32) -> StrokeOptions {
+    StrokeOptions {
+        color,
+        line_width: Figure::new(width),
+        ..StrokeOptions::default()
+    }
+}
+
+/// Every scene is drawn over an opaque background so that the goldens don't
+/// depend on the theme.
+fn clear(renderer: &CanvasRenderer) {
+    renderer.fill_rect(&scene_bounds(), Color::WHITE);
+}
+
+fn strokes(renderer: CanvasRenderer, _content_area: &ContentArea) {
+    clear(&renderer);
+    for (index, width) in [1., 2., 4., 8.].iter().enumerate() {
+        renderer.stroke_rect(
+            &rect(16. + index as f32 * 76., 16., 60., 60.),
+            &stroke(Color::BLACK, *width),
+        );
+    }
+    renderer.stroke_line(
+        Point::<f32, Scaled>::new(16., 100.),
+        Point::<f32, Scaled>::new(304., 100.),
+        &stroke(Color::CRIMSON, 1.),
+    );
+    renderer.stroke_line(
+        Point::<f32, Scaled>::new(16., 120.),
+        Point::<f32, Scaled>::new(150., 224.),
+        &stroke(Color::STEELBLUE, 3.),
+    );
+    renderer.stroke_path(
+        &Path::new(Point::new(180., 224.))
+            .line_to(Point::new(240., 120.))
+            .line_to(Point::new(300., 224.))
+            .close(),
+        &stroke(Color::SEAGREEN, 2.),
+    );
+}
+
+fn clipping(renderer: CanvasRenderer, _content_area: &ContentArea) {
+    clear(&renderer);
+    let clipped = renderer.clip_to(rect(40., 40., 120., 80.));
+    // Only the part of this rectangle within the clip is drawn.
+    clipped.fill_rect(&scene_bounds(), Color::STEELBLUE);
+    clipped.stroke_line(
+        Point::<f32, Scaled>::new(0., 0.),
+        Point::<f32, Scaled>::new(320., 240.),
+        &stroke(Color::WHITE, 4.),
+    );
+    renderer.stroke_rect(&rect(40., 40., 120., 80.), &stroke(Color::BLACK, 1.));
+    renderer.fill_rect(&rect(200., 140., 80., 60.), Color::GOLDENROD);
+}
+
+fn text_baselines(renderer: CanvasRenderer, _content_area: &ContentArea) {
+    clear(&renderer);
+    for (index, size) in [12., 16., 24., 36.].iter().enumerate() {
+        let baseline = 40. + index as f32 * 56.;
+        renderer.stroke_line(
+            Point::<f32, Scaled>::new(0., baseline),
+            Point::<f32, Scaled>::new(320., baseline),
+            &stroke(Color::CRIMSON, 1.),
+        );
+        renderer.render_text(
+            "Baseline gjy",
+            Point::<f32, Scaled>::new(16., baseline),
+            &TextOptions {
+                color: Color::BLACK,
+                text_size: Figure::new(*size),
+                ..TextOptions::default()
+            },
+        );
+    }
+}
+
+fn nested_clips(renderer: CanvasRenderer, _content_area: &ContentArea) {
+    clear(&renderer);
+    let outer = renderer.clip_to(rect(20., 20., 200., 160.));
+    outer.fill_rect(&scene_bounds(), Color::LIGHTGRAY);
+    // The inner clip is in canvas coordinates, and is limited to the outer
+    // clip.
+    let inner = outer.clip_to(rect(120., 100., 180., 120.));
+    inner.fill_rect(&scene_bounds(), Color::MEDIUMPURPLE);
+    inner.stroke_rect(&rect(130., 110., 40., 40.), &stroke(Color::BLACK, 2.));
+}
+
+fn shaped_clips(renderer: CanvasRenderer, _content_area: &ContentArea) {
+    clear(&renderer);
+    renderer
+        .clip_to_rounded_rect(rect(16., 16., 136., 96.), 24.)
+        .fill_rect(&scene_bounds(), Color::STEELBLUE);
+
+    let triangle = Path::new(Point::new(240., 16.))
+        .line_to(Point::new(304., 112.))
+        .line_to(Point::new(176., 112.));
+    renderer
+        .clip_to_path(&triangle)
+        .fill_rect(&scene_bounds(), Color::SEAGREEN);
+
+    // A rectangular clip within a rounded clip keeps the rounded corners that
+    // fall inside of it.
+    renderer
+        .clip_to_rounded_rect(rect(16., 128., 288., 96.), 48.)
+        .clip_to(rect(0., 128., 160., 112.))
+        .fill_rect(&scene_bounds(), Color::CRIMSON);
+}
+
+fn dashes(renderer: CanvasRenderer, _content_area: &ContentArea) {
+    clear(&renderer);
+    // Dashed grid lines.
+    let grid = renderer.with_stroke_style(StrokeStyle::dashed([4., 4.]));
+    for index in 0..5 {
+        let y = 16. + index as f32 * 24.;
+        grid.stroke_line(
+            Point::<f32, Scaled>::new(16., y),
+            Point::<f32, Scaled>::new(144., y),
+            &stroke(Color::GRAY, 1.),
+        );
+    }
+
+    // A selection marquee, with its pattern continuing around the corners.
+    renderer
+        .with_stroke_style(StrokeStyle::dashed([6., 3.]).with_dash_offset(2.))
+        .stroke_rect(&rect(176., 16., 128., 96.), &stroke(Color::BLACK, 2.));
+
+    // Square caps extend each dash by half of the line width.
+    renderer
+        .with_stroke_style(StrokeStyle::dashed([8.]).with_cap(LineCap::Square))
+        .stroke_line(
+            Point::<f32, Scaled>::new(20., 144.),
+            Point::<f32, Scaled>::new(300., 144.),
+            &stroke(Color::STEELBLUE, 4.),
+        );
+
+    // Dashes continue from one segment of a path to the next, and the dash
+    // pattern is scaled with the renderer.
+    renderer
+        .transformed(&Transform::new(2., Vector::default()))
+        .with_stroke_style(StrokeStyle::dashed([5., 2., 1., 2.]))
+        .stroke_path(
+            &Path::new(Point::new(8., 112.))
+                .line_to(Point::new(64., 84.))
+                .line_to(Point::new(120., 112.)),
+            &stroke(Color::SEAGREEN, 1.),
+        );
+}
+
+fn pixel_snapping(renderer: CanvasRenderer, _content_area: &ContentArea) {
+    clear(&renderer);
+    // Lines and rectangles between pixels are moved onto whole pixels, so
+    // both backends draw them without blending.
+    let snapped = renderer.with_pixel_snapping(true);
+    for index in 0..8 {
+        let offset = index as f32 * 0.3;
+        snapped.stroke_line(
+            Point::<f32, Scaled>::new(16. + index as f32 * 16. + offset, 16.),
+            Point::<f32, Scaled>::new(16. + index as f32 * 16. + offset, 112.),
+            &stroke(Color::BLACK, 1.),
+        );
+        snapped.fill_rect(
+            &rect(160. + offset, 16. + index as f32 * 12. + offset, 144.4, 6.6),
+            Color::STEELBLUE,
+        );
+    }
+    snapped.stroke_rect(&rect(16.4, 128.4, 288.2, 96.2), &stroke(Color::CRIMSON, 2.));
+}
+
+/// A heatmap generated at runtime, quantized into bands of flat color.
+#[derive(Default)]
+struct Textures {
+    heatmap: Option<Texture>,
+}
+
+impl Renderable for Textures {
+    fn initialize(&mut self, _context: &Context<Canvas>) {
+        let size = Size::<u32, Pixels>::new(96, 64);
+        let mut rgba = Vec::with_capacity((size.width * size.height * 4) as usize);
+        for y in 0..size.height {
+            for x in 0..size.width {
+                let heat = ((x + y) / 16 * 32).min(255) as u8;
+                rgba.extend_from_slice(&[heat, 0, 255 - heat, 255]);
+            }
+        }
+        self.heatmap = Some(Texture::from_rgba(size, rgba).unwrap());
+    }
+
+    fn render(&mut self, renderer: CanvasRenderer, _content_area: &ContentArea) {
+        clear(&renderer);
+        if let Some(heatmap) = &self.heatmap {
+            renderer.draw_texture(heatmap, Point::<f32, Scaled>::new(16., 16.));
+            renderer
+                .clip_to(rect(144., 16., 48., 32.))
+                .draw_texture(heatmap, Point::<f32, Scaled>::new(144., 16.));
+        }
+    }
+}
+
+#[derive(Default)]
+struct Images {
+    image: Option<Image>,
+}
+
+impl Renderable for Images {
+    fn initialize(&mut self, context: &Context<Canvas>) {
+        let image = Image::from(Asset::build().path(vec!["rolls.jpg"]).finish());
+        let loaded_context = context.clone();
+        image.load(
+            Callback::new(move |_| {
+                ASSETS_LOADED.store(true, Ordering::SeqCst);
+                loaded_context.send_command(Command::Refresh);
+            }),
+            Callback::new(|err| panic!("error loading asset: {}", err)),
+            context.frontend(),
+        );
+        self.image = Some(image);
+    }
+
+    fn render(&mut self, renderer: CanvasRenderer, _content_area: &ContentArea) {
+        clear(&renderer);
+        if let Some(image) = &self.image {
+            renderer.draw_image(image, Point::<f32, Scaled>::new(16., 16.));
+            renderer
+                .clip_to(rect(176., 16., 64., 64.))
+                .draw_image(image, Point::<f32, Scaled>::new(176., 16.));
+        }
+    }
+}
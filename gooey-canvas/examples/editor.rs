@@ -0,0 +1,157 @@
+//! A single line of editable-looking text. Clicking places the caret, and
+//! dragging selects text, using the positions measured by
+//! [`CanvasRenderer::layout_text()`].
+
+use std::{
+    ops::Range,
+    sync::{Arc, Mutex},
+};
+
+use gooey::{
+    core::{
+        figures::{Figure, Point},
+        styles::Color,
+        Scaled,
+    },
+    frontends::rasterizer::ContentArea,
+    renderer::{Renderer, TextOptions},
+    App,
+};
+use gooey_canvas::{
+    AppExt, Canvas, CanvasRenderer, DragPhase, DragTracker, EventStatus, PointerEvent,
+    PointerEventKind, Renderable, TextLayout,
+};
+
+/// The text being edited. The trailing spaces leave room to see the
+/// selection without any glyphs drawn over it.
+const TEXT: &str = "let answer = 42;    ";
+
+const SELECTION_COLOR: Color = Color::LIGHTSKYBLUE;
+
+fn app(editor: Editor) -> App {
+    App::from_root(move |_storage| Canvas::new(editor.clone())).with_canvas()
+}
+
+/// Returns where the text's baseline starts.
+fn baseline() -> Point<f32, Scaled> {
+    Point::new(16., 48.)
+}
+
+fn main() {
+    app(Editor::default()).run()
+}
+
+#[derive(Debug, Default, Clone)]
+struct Editor {
+    caret: usize,
+    selection: Option<Range<usize>>,
+    drag: DragTracker,
+    /// The layout of the last frame, used to hit-test the pointer. It's
+    /// shared so that tests can inspect it.
+    layout: Arc<Mutex<Option<TextLayout>>>,
+}
+
+impl Editor {
+    fn index_at(&self, location: Point<f32, Scaled>) -> Option<usize> {
+        self.layout
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|layout| layout.index_at(location))
+    }
+}
+
+impl Renderable for Editor {
+    fn pointer(&mut self, event: &PointerEvent) -> EventStatus {
+        if let PointerEventKind::Down(_) = event.kind {
+            if let Some(index) = self.index_at(event.location) {
+                self.caret = index;
+                self.selection = None;
+            }
+        }
+        if let Some(drag) = self.drag.pointer(event) {
+            if let (Some(start), Some(end)) =
+                (self.index_at(drag.start), self.index_at(drag.location))
+            {
+                self.caret = end;
+                self.selection = Some(start.min(end)..start.max(end));
+            }
+            if drag.phase == DragPhase::Ended
+                && self.selection.as_ref().map_or(false, Range::is_empty)
+            {
+                self.selection = None;
+            }
+        }
+        EventStatus::Processed
+    }
+
+    fn render(&mut self, renderer: CanvasRenderer, _content_area: &ContentArea) {
+        renderer.fill_rect(&renderer.clip_bounds(), Color::WHITE);
+
+        let options = TextOptions {
+            color: Color::BLACK,
+            text_size: Figure::new(20.),
+            ..TextOptions::default()
+        };
+        let layout = renderer.layout_text(TEXT, baseline(), &options);
+        if let Some(selection) = &self.selection {
+            renderer.draw_selection(&layout, selection.clone(), SELECTION_COLOR);
+        }
+        renderer.render_text(TEXT, baseline(), &options);
+        renderer.draw_caret(&layout, self.caret, Color::BLACK);
+        *self.layout.lock().unwrap() = Some(layout);
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use gooey::core::{
+        figures::{Rectlike, Size},
+        styles::SystemTheme,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn carets_and_selections_follow_the_text() -> anyhow::Result<()> {
+        let trailing_spaces = TEXT.trim_end().len()..TEXT.len();
+        let layout = Arc::<Mutex<Option<TextLayout>>>::default();
+        let screenshot = app(Editor {
+            caret: trailing_spaces.end,
+            selection: Some(trailing_spaces.clone()),
+            drag: DragTracker::default(),
+            layout: layout.clone(),
+        })
+        .headless()
+        .screenshot(Size::new(320, 96), SystemTheme::Light, None)
+        .await?
+        .to_rgba8();
+
+        let layout = layout.lock().unwrap().clone().expect("no frame was drawn");
+        // Hit-testing the middle of each caret finds the caret's index, and
+        // carets move right through the text.
+        let mut previous = None;
+        for (index, _) in TEXT.char_indices() {
+            let caret = layout.caret_rect(index).as_sized();
+            let center = Point::new(
+                caret.origin.x + caret.size.width / 2.,
+                caret.origin.y + caret.size.height / 2.,
+            );
+            assert_eq!(layout.index_at(center), index);
+            if let Some(previous) = previous {
+                assert!(caret.origin.x > previous, "caret {} moved left", index);
+            }
+            previous = Some(caret.origin.x);
+        }
+
+        // The selection covers the trailing spaces, where no glyphs are drawn.
+        let selection = layout.selection_rect(trailing_spaces).as_sized();
+        let x = (selection.origin.x + selection.size.width / 2.) as u32;
+        let selected =
+            screenshot.get_pixel(x, (selection.origin.y + selection.size.height / 2.) as u32);
+        let background =
+            screenshot.get_pixel(x, (selection.origin.y + selection.size.height) as u32 + 8);
+        assert_ne!(selected, background, "the selection wasn't drawn");
+        Ok(())
+    }
+}
@@ -1,4 +1,13 @@
-use std::path::PathBuf;
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+
+use image::{Rgba, RgbaImage};
+
+/// When this environment variable is set to anything other than `0`,
+/// [`assert_snapshot()`] replaces the golden images instead of comparing
+/// against them.
+pub const BLESS_VAR: &str = "BLESS_SNAPSHOTS";
 
 /// Returns a path within the `target` directory. This function assumes the exe
 /// running is an example.
@@ -19,3 +28,174 @@ pub fn snapshot_path(example: &str, name: &str) -> std::io::Result<PathBuf> {
 
     Ok(examples_dir.join(name))
 }
+
+/// Returns the path of the committed golden image `name` for `example`.
+pub fn golden_path(example: &str, name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("examples")
+        .join("snapshots")
+        .join(example)
+        .join(name)
+}
+
+/// How different an image may be from its golden.
+#[derive(Debug, Clone, Copy)]
+pub struct Tolerance {
+    /// The perceptual difference between two pixels, from 0 to 1, above which
+    /// they are considered different.
+    pub threshold: f32,
+    /// The fraction of pixels that may differ.
+    pub mismatched: f32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            threshold: 0.1,
+            mismatched: 0.,
+        }
+    }
+}
+
+/// The result of comparing an image against its golden.
+pub struct Comparison {
+    /// The number of pixels that differ.
+    pub mismatched: u32,
+    /// The number of pixels that may differ.
+    pub allowed: u32,
+    /// The golden faded to gray, with the pixels that differ in red.
+    pub diff: RgbaImage,
+}
+
+impl Comparison {
+    pub fn passed(&self) -> bool {
+        self.mismatched <= self.allowed
+    }
+}
+
+/// Compares `actual` against `golden`, which must be the same size.
+pub fn compare(actual: &RgbaImage, golden: &RgbaImage, tolerance: Tolerance) -> Comparison {
+    // The largest possible value of `color_delta()`.
+    const MAX_DELTA: f32 = 35215.;
+    let max_delta = MAX_DELTA * tolerance.threshold * tolerance.threshold;
+
+    let mut mismatched = 0;
+    let mut diff = RgbaImage::new(golden.width(), golden.height());
+    for (x, y, expected) in golden.enumerate_pixels() {
+        let pixel = actual.get_pixel(x, y);
+        if color_delta(pixel, expected) > max_delta {
+            mismatched += 1;
+            diff.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+        } else {
+            let gray = (255. - (255. - luma(expected)) * 0.1) as u8;
+            diff.put_pixel(x, y, Rgba([gray, gray, gray, 255]));
+        }
+    }
+
+    Comparison {
+        mismatched,
+        allowed: ((golden.width() * golden.height()) as f32 * tolerance.mismatched) as u32,
+        diff,
+    }
+}
+
+/// Compares `actual` against the golden image `name` for `example`.
+///
+/// `actual` is always written to [`snapshot_path()`]. If the comparison
+/// fails, an image highlighting the differences is written next to it. When
+/// [`BLESS_VAR`] is set, `actual` becomes the new golden instead.
+pub fn assert_snapshot(
+    example: &str,
+    name: &str,
+    actual: &RgbaImage,
+    tolerance: Tolerance,
+) -> anyhow::Result<()> {
+    actual.save(snapshot_path(example, name)?)?;
+
+    let golden_path = golden_path(example, name);
+    if blessing() {
+        if let Some(parent) = golden_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        actual.save(&golden_path)?;
+        return Ok(());
+    }
+
+    if !golden_path.exists() {
+        anyhow::bail!(
+            "{} has no golden at {}. Run with {}=1 to create it.",
+            name,
+            golden_path.display(),
+            BLESS_VAR
+        );
+    }
+    let golden = image::open(&golden_path)?.to_rgba8();
+    if golden.dimensions() != actual.dimensions() {
+        anyhow::bail!(
+            "{} is {:?}, but its golden is {:?}",
+            name,
+            actual.dimensions(),
+            golden.dimensions()
+        );
+    }
+
+    let comparison = compare(actual, &golden, tolerance);
+    if !comparison.passed() {
+        let diff_path = snapshot_path(example, &diff_name(name))?;
+        comparison.diff.save(&diff_path)?;
+        anyhow::bail!(
+            "{}: {} pixels differ from the golden (at most {} allowed). See {}.",
+            name,
+            comparison.mismatched,
+            comparison.allowed,
+            diff_path.display()
+        );
+    }
+
+    Ok(())
+}
+
+fn blessing() -> bool {
+    std::env::var(BLESS_VAR).map_or(false, |value| !value.is_empty() && value != "0")
+}
+
+fn diff_name(name: &str) -> String {
+    let path = Path::new(name);
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(name);
+    format!("{}-diff.png", stem)
+}
+
+/// Returns the squared perceptual distance between two colors in the YIQ
+/// color space, after blending both with white.
+fn color_delta(a: &Rgba<u8>, b: &Rgba<u8>) -> f32 {
+    let (a, b) = (blend_with_white(a), blend_with_white(b));
+    let y = yiq_y(a) - yiq_y(b);
+    let i = yiq_i(a) - yiq_i(b);
+    let q = yiq_q(a) - yiq_q(b);
+    0.5053 * y * y + 0.299 * i * i + 0.1957 * q * q
+}
+
+fn blend_with_white(color: &Rgba<u8>) -> [f32; 3] {
+    let alpha = f32::from(color.0[3]) / 255.;
+    let blend = |channel: u8| 255. + (f32::from(channel) - 255.) * alpha;
+    [blend(color.0[0]), blend(color.0[1]), blend(color.0[2])]
+}
+
+fn yiq_y([r, g, b]: [f32; 3]) -> f32 {
+    r * 0.298_895_3 + g * 0.586_622_5 + b * 0.114_482_23
+}
+
+fn yiq_i([r, g, b]: [f32; 3]) -> f32 {
+    r * 0.595_977_99 - g * 0.274_176_1 - b * 0.321_801_89
+}
+
+fn yiq_q([r, g, b]: [f32; 3]) -> f32 {
+    r * 0.211_470_17 - g * 0.522_617_11 + b * 0.311_146_94
+}
+
+fn luma(color: &Rgba<u8>) -> f32 {
+    yiq_y(blend_with_white(color))
+}
@@ -0,0 +1,177 @@
+//! Four independent canvases sharing one window. Each canvas keeps its own
+//! size, pointer state and animation, and only draws within its own bounds.
+
+use std::time::Duration;
+
+use gooey::{
+    core::{
+        figures::{Figure, Point, Rect, Size, SizedRect, Vector},
+        styles::Color,
+        Scaled,
+    },
+    frontends::rasterizer::ContentArea,
+    renderer::{Renderer, StrokeOptions, TextOptions},
+    widgets::layout::{Dimension, Layout, WidgetLayout},
+    App,
+};
+use gooey_canvas::{
+    AppExt, Canvas, CanvasRenderer, EventStatus, PointerEvent, PointerEventKind, Renderable,
+};
+
+/// The background of each pane, from the top-left pane in reading order.
+const PANE_COLORS: [Color; 4] = [
+    Color::STEELBLUE,
+    Color::SEAGREEN,
+    Color::GOLDENROD,
+    Color::MEDIUMPURPLE,
+];
+
+fn app() -> App {
+    App::from_root(|storage| {
+        let mut layout = Layout::build(storage);
+        for (index, color) in PANE_COLORS.iter().enumerate() {
+            let column = (index % 2) as f32;
+            let row = (index / 2) as f32;
+            layout = layout.with(
+                None,
+                Canvas::new(Pane::new(*color, index == 0)),
+                WidgetLayout::build()
+                    .left(Dimension::percent(column * 0.5))
+                    .top(Dimension::percent(row * 0.5))
+                    .width(Dimension::percent(0.5))
+                    .height(Dimension::percent(0.5))
+                    .finish(),
+            );
+        }
+        layout.finish()
+    })
+    .with_canvas()
+}
+
+fn main() {
+    app().run()
+}
+
+/// One of the canvases. Clicking a pane moves its marker, and the first pane
+/// animates a spinner while the others only draw when they change.
+#[derive(Debug)]
+struct Pane {
+    color: Color,
+    size: Size<f32, Scaled>,
+    marker: Option<Point<f32, Scaled>>,
+    spinner: Option<f32>,
+}
+
+impl Pane {
+    fn new(color: Color, animated: bool) -> Self {
+        Self {
+            color,
+            size: Size::default(),
+            marker: None,
+            spinner: if animated { Some(0.) } else { None },
+        }
+    }
+}
+
+impl Renderable for Pane {
+    fn resized(&mut self, size: Size<f32, Scaled>) {
+        self.size = size;
+    }
+
+    fn update(&mut self, elapsed: Duration) -> bool {
+        if let Some(angle) = &mut self.spinner {
+            *angle =
+                (*angle + elapsed.as_secs_f32() * std::f32::consts::PI) % std::f32::consts::TAU;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn pointer(&mut self, event: &PointerEvent) -> EventStatus {
+        if let PointerEventKind::Down(_) = event.kind {
+            self.marker = Some(event.location);
+            EventStatus::Processed
+        } else {
+            EventStatus::Ignored
+        }
+    }
+
+    fn render(&mut self, renderer: CanvasRenderer, _content_area: &ContentArea) {
+        // This rectangle is far larger than the pane, but drawing is limited
+        // to the pane's bounds.
+        renderer.fill_rect(
+            &Rect::<f32, Scaled>::from(SizedRect::new(
+                Point::new(-1000., -1000.),
+                Size::new(3000., 3000.),
+            )),
+            self.color,
+        );
+
+        let center = Point::<f32, Scaled>::new(self.size.width / 2., self.size.height / 2.);
+        renderer.render_text(
+            &format!("{:.0} x {:.0}", self.size.width, self.size.height),
+            Point::<f32, Scaled>::new(12., 24.),
+            &TextOptions {
+                color: Color::WHITE,
+                text_size: Figure::new(16.),
+                ..TextOptions::default()
+            },
+        );
+
+        if let Some(angle) = self.spinner {
+            renderer.stroke_line(
+                center,
+                center + Vector::new(angle.cos() * 32., angle.sin() * 32.),
+                &StrokeOptions {
+                    color: Color::WHITE,
+                    line_width: Figure::new(4.),
+                    ..StrokeOptions::default()
+                },
+            );
+        }
+
+        if let Some(marker) = self.marker {
+            renderer.fill_rect(
+                &Rect::from(SizedRect::new(
+                    marker - Vector::new(4., 4.),
+                    Size::new(8., 8.),
+                )),
+                Color::WHITE,
+            );
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use gooey::core::styles::SystemTheme;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn panes_stay_within_their_bounds() -> anyhow::Result<()> {
+        let screenshot = app()
+            .headless()
+            .screenshot(Size::new(320, 240), SystemTheme::Light, None)
+            .await?
+            .to_rgba8();
+
+        // Sample two corners of each pane, away from its text and spinner. If
+        // a pane drew outside of its bounds, it would cover a neighbor.
+        let mut samples = Vec::new();
+        for index in 0..PANE_COLORS.len() as u32 {
+            let (left, top) = ((index % 2) * 160, (index / 2) * 120);
+            let top_right = screenshot.get_pixel(left + 150, top + 40);
+            let bottom_left = screenshot.get_pixel(left + 10, top + 110);
+            assert_eq!(top_right, bottom_left, "pane {} isn't filled", index);
+            assert!(
+                !samples.contains(top_right),
+                "pane {} was drawn over",
+                index
+            );
+            samples.push(*top_right);
+        }
+        Ok(())
+    }
+}
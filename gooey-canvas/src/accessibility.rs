@@ -0,0 +1,84 @@
+use gooey::core::{figures::Rect, Scaled};
+
+/// Describes a [`Canvas`](crate::Canvas) to assistive technology, which can't
+/// see what the canvas draws. Provide one with
+/// [`Renderable::accessibility()`](crate::Renderable::accessibility) or
+/// [`Builder::with_accessibility()`](crate::Builder::with_accessibility).
+///
+/// In the browser, the tree is emitted as fallback content inside the
+/// `<canvas>` element: the canvas is labeled with its role, and each region
+/// is a focusable button whose bounds are reported to screen magnifiers. The
+/// rasterizer frontend has no accessibility hooks yet, so natively the tree
+/// is only available from [`Canvas::accessibility()`](crate::Canvas::accessibility).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessibilityTree {
+    /// The name announced for the canvas.
+    pub label: String,
+    /// What kind of content the canvas contains.
+    pub role: CanvasRole,
+    /// The parts of the canvas that can be focused, in focus order.
+    pub regions: Vec<AccessibleRegion>,
+}
+
+impl AccessibilityTree {
+    /// Returns a tree for an [image](CanvasRole::Image) labeled `label`, with
+    /// no regions.
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            role: CanvasRole::Image,
+            regions: Vec::new(),
+        }
+    }
+
+    /// Sets the role of the canvas and returns self.
+    #[must_use]
+    pub const fn with_role(mut self, role: CanvasRole) -> Self {
+        self.role = role;
+        self
+    }
+
+    /// Adds a focusable region named `name` covering `bounds`, in canvas
+    /// coordinates, and returns self.
+    #[must_use]
+    pub fn with_region(mut self, name: impl Into<String>, bounds: Rect<f32, Scaled>) -> Self {
+        self.regions.push(AccessibleRegion {
+            name: name.into(),
+            bounds,
+        });
+        self
+    }
+}
+
+/// The kind of content a [`Canvas`](crate::Canvas) contains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CanvasRole {
+    /// A picture, such as a chart or diagram, that is described by its label.
+    Image,
+    /// A drawing whose regions are meaningful on their own.
+    Figure,
+    /// An interactive surface that handles its own input, such as a game or
+    /// an editor.
+    Application,
+}
+
+impl CanvasRole {
+    /// Returns the ARIA role matching this role.
+    #[must_use]
+    pub const fn aria_role(self) -> &'static str {
+        match self {
+            Self::Image => "img",
+            Self::Figure => "figure",
+            Self::Application => "application",
+        }
+    }
+}
+
+/// A focusable part of a [`Canvas`](crate::Canvas).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessibleRegion {
+    /// The name announced when the region is focused.
+    pub name: String,
+    /// The area of the canvas the region covers, in canvas coordinates.
+    pub bounds: Rect<f32, Scaled>,
+}
@@ -1,6 +1,11 @@
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, Weak,
+    },
+    time::Duration,
 };
 
 use gooey::{
@@ -8,10 +13,10 @@ use gooey::{
         assets::Image,
         figures::{
             DisplayScale, Displayable, Figure, Point, Rect, Rectlike, Scale, Scaled, Size,
-            SizedRect,
+            SizedRect, Vector,
         },
         styles::{Color, Style, SystemTheme},
-        Context, Pixels, TransmogrifierContext, WidgetId,
+        Callback, Context, Pixels, TransmogrifierContext, WidgetId,
     },
     frontends::{
         browser::{
@@ -22,11 +27,20 @@ use gooey::{
     },
     renderer::{Renderer, StrokeOptions, TextMetrics, TextOptions},
 };
-use js_sys::Function;
-use wasm_bindgen::{prelude::Closure, JsCast, JsValue};
-use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement};
+use js_sys::{Array, Function, Reflect, Uint8Array};
+use wasm_bindgen::{prelude::Closure, Clamped, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    CanvasRenderingContext2d, CanvasWindingRule, FontFace, HtmlCanvasElement, HtmlElement,
+    HtmlImageElement, ImageData, MouseEvent, ResizeObserver, WheelEvent,
+};
 
-use crate::{Canvas, CanvasRenderer, CanvasTransmogrifier, Command};
+use crate::{
+    clip::ClipShape, effects::Effects, fonts::FontSource, AccessibilityTree, Canvas, CanvasBackend,
+    CanvasFont, CanvasImage, CanvasRenderer, CanvasStatistics, CanvasTransmogrifier, Command,
+    CursorIcon, DragEvent, DragEventKind, DragPayload, DroppedFile, ImageSmoothing, Invalidation,
+    Modifiers, PointerButton, PointerDevice, PointerEvent, PointerEventKind, StrokeStyle, Texture,
+};
 
 fn canvas_element(widget_id: &WidgetId) -> Option<HtmlCanvasElement> {
     window_document()
@@ -46,70 +60,628 @@ impl BrowserRenderer {
             .and_then(|c| c.dyn_into().ok())
     }
 
+    /// Limits drawing to the clip rectangle and shapes. Clipping doesn't move
+    /// the origin, matching Kludgine: coordinates are always relative to the
+    /// canvas.
     fn clip(&self, context: &CanvasRenderingContext2d) {
+        let clip = Rect::from(self.clip.cast::<f32>())
+            .to_pixels(&self.scale)
+            .cast::<f64>()
+            .as_sized();
         context.begin_path();
         context.rect(
-            self.clip.origin.x,
-            self.clip.origin.y,
-            self.clip.size.width,
-            self.clip.size.height,
+            clip.origin.x,
+            clip.origin.y,
+            clip.size.width,
+            clip.size.height,
         );
         context.clip();
-        context
-            .translate(self.clip.origin.x, self.clip.origin.y)
-            .unwrap();
+
+        for shape in &self.shapes {
+            context.begin_path();
+            match shape {
+                ClipShape::RoundedRect { rect, radius } => {
+                    let rect = rect.to_pixels(&self.scale).cast::<f64>().as_extents();
+                    let radius = self.length_to_pixels(*radius);
+                    context.move_to(rect.origin.x + radius, rect.origin.y);
+                    for (corner, next) in [
+                        (
+                            (rect.extent.x, rect.origin.y),
+                            (rect.extent.x, rect.extent.y),
+                        ),
+                        (
+                            (rect.extent.x, rect.extent.y),
+                            (rect.origin.x, rect.extent.y),
+                        ),
+                        (
+                            (rect.origin.x, rect.extent.y),
+                            (rect.origin.x, rect.origin.y),
+                        ),
+                        (
+                            (rect.origin.x, rect.origin.y),
+                            (rect.extent.x, rect.origin.y),
+                        ),
+                    ]
+                    .iter()
+                    {
+                        context
+                            .arc_to(corner.0, corner.1, next.0, next.1, radius)
+                            .unwrap();
+                    }
+                }
+                ClipShape::Polygon(points) => {
+                    for (index, point) in points.iter().enumerate() {
+                        let point = point.to_pixels(&self.scale).cast::<f64>();
+                        if index == 0 {
+                            context.move_to(point.x, point.y);
+                        } else {
+                            context.line_to(point.x, point.y);
+                        }
+                    }
+                }
+            }
+            context.close_path();
+            context.clip_with_canvas_winding_rule(CanvasWindingRule::Evenodd);
+        }
+    }
+
+    pub(crate) fn set_effects(&mut self, effects: Effects) {
+        self.effects = effects;
+    }
+
+    pub(crate) fn set_stroke_style(&mut self, stroke_style: StrokeStyle) {
+        self.stroke_style = stroke_style;
+    }
+
+    pub(crate) fn set_image_smoothing(&mut self, image_smoothing: ImageSmoothing) {
+        self.image_smoothing = image_smoothing;
+    }
+
+    /// Configures how `context` resamples images.
+    fn apply_image_smoothing(&self, context: &CanvasRenderingContext2d) {
+        match self.image_smoothing.css_name() {
+            Some(quality) => {
+                context.set_image_smoothing_enabled(true);
+                // web-sys doesn't bind `imageSmoothingQuality`.
+                Reflect::set(
+                    context,
+                    &JsValue::from_str("imageSmoothingQuality"),
+                    &JsValue::from_str(quality),
+                )
+                .unwrap();
+            }
+            None => context.set_image_smoothing_enabled(false),
+        }
+    }
+
+    /// Configures `context` to stroke with `options` and the stroke style of
+    /// this renderer.
+    fn apply_stroke(&self, context: &CanvasRenderingContext2d, options: &StrokeOptions) {
+        context.set_stroke_style(&JsValue::from_str(&options.color.as_css_string()));
+        context.set_line_width(self.length_to_pixels(options.line_width.get()));
+        context.set_line_cap(self.stroke_style.cap.css_name());
+        context.set_line_join(self.stroke_style.join.css_name());
+        context.set_miter_limit(f64::from(self.stroke_style.miter_limit));
+        if let Some(pattern) = self.stroke_style.dash_pattern() {
+            let dashes = pattern
+                .into_iter()
+                .map(|length| JsValue::from_f64(self.length_to_pixels(length)))
+                .collect::<Array>();
+            context.set_line_dash(&dashes).unwrap();
+            context.set_line_dash_offset(self.length_to_pixels(self.stroke_style.dash_offset));
+        }
+    }
+
+    /// Strokes the lines joining `points`, in canvas coordinates, as a single
+    /// path so that corners are joined. When `closed` is true, the last point
+    /// repeats the first and is replaced by closing the path.
+    pub(crate) fn stroke_path(
+        &self,
+        points: &[Point<f32, Scaled>],
+        closed: bool,
+        options: &StrokeOptions,
+    ) {
+        if let Some(context) = self.rendering_context() {
+            context.save();
+            self.clip(&context);
+            self.apply_stroke(&context, options);
+            let points = match points.split_last() {
+                Some((_, open)) if closed => open,
+                _ => points,
+            };
+            context.begin_path();
+            for (index, point) in points.iter().enumerate() {
+                let point = point.to_pixels(&self.scale).cast::<f64>();
+                if index == 0 {
+                    context.move_to(point.x, point.y);
+                } else {
+                    context.line_to(point.x, point.y);
+                }
+            }
+            if closed {
+                context.close_path();
+            }
+            context.stroke();
+            context.restore();
+        }
+    }
+
+    /// Configures `context` to draw the shadow and blur of this renderer.
+    fn apply_effects(&self, context: &CanvasRenderingContext2d) {
+        if let Some(shadow) = &self.effects.shadow {
+            let offset = Point::<f32, Scaled>::new(shadow.offset.x, shadow.offset.y)
+                .to_pixels(&self.scale)
+                .cast::<f64>();
+            context.set_shadow_offset_x(offset.x);
+            context.set_shadow_offset_y(offset.y);
+            context.set_shadow_blur(self.length_to_pixels(shadow.blur_radius));
+            context.set_shadow_color(&shadow.color.as_css_string());
+        }
+        if self.effects.blur_radius > 0. {
+            // The blur filter's radius is its standard deviation, which is
+            // half of the distance the blur fades out over.
+            context.set_filter(&format!(
+                "blur({}px)",
+                self.length_to_pixels(self.effects.blur_radius) / 2.
+            ));
+        }
+    }
+
+    /// Configures `context` to draw text with the size and family of
+    /// `options`. Options without either use the context's default font.
+    fn apply_font(&self, context: &CanvasRenderingContext2d, options: &TextOptions) {
+        if options.text_size.get() <= 0. && options.font_family.is_none() {
+            return;
+        }
+        let size = if options.text_size.get() > 0. {
+            self.length_to_pixels(options.text_size.get())
+        } else {
+            DEFAULT_FONT_SIZE
+        };
+        let family = match &options.font_family {
+            // Registered families are quoted so that names with spaces or
+            // digits parse, and fall back to the default family.
+            Some(family) => format!("\"{}\", sans-serif", family.replace('"', "")),
+            None => String::from("sans-serif"),
+        };
+        context.set_font(&format!("{}px {}", size, family));
+    }
+
+    /// Converts a length in pixels to scaled units.
+    fn pixels_to_length(&self, pixels: f64) -> f32 {
+        let pixels = pixels as f32;
+        Size::<f32, Pixels>::new(pixels, pixels)
+            .to_scaled(&self.scale)
+            .width
+    }
+
+    /// Converts a length in scaled units to pixels.
+    fn length_to_pixels(&self, length: f32) -> f64 {
+        f64::from(
+            Size::<f32, Scaled>::new(length, length)
+                .to_pixels(&self.scale)
+                .width,
+        )
+    }
+
+    /// Returns a renderer that is also clipped to `shape`, in canvas
+    /// coordinates.
+    pub(crate) fn clip_to_shape(&self, shape: ClipShape) -> Self {
+        let mut clipped = self.clip_to(shape.bounds());
+        clipped.shapes.push(shape);
+        clipped
+    }
+
+    /// Draws each `(source, location)` region of `image`, scaled by `scale`.
+    pub(crate) fn draw_image_regions(
+        &self,
+        image: &Image,
+        regions: impl Iterator<Item = (Rect<u32, Pixels>, Point<f32, Scaled>)>,
+        scale: f32,
+    ) {
+        if let (Some(context), Some(element)) = (self.rendering_context(), image_element(image)) {
+            context.save();
+            self.clip(&context);
+            self.apply_effects(&context);
+            self.apply_image_smoothing(&context);
+            for (source, location) in regions {
+                let source = source.cast::<f64>().as_sized();
+                let location = location.to_pixels(&self.scale).cast::<f64>();
+                context
+                    .draw_image_with_html_image_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+                        &element,
+                        source.origin.x,
+                        source.origin.y,
+                        source.size.width,
+                        source.size.height,
+                        location.x,
+                        location.y,
+                        source.size.width * f64::from(scale),
+                        source.size.height * f64::from(scale),
+                    )
+                    .unwrap();
+            }
+            context.restore();
+        }
+    }
+
+    /// Returns the ascent and descent of the font `options` draws with, or
+    /// `None` if the browser doesn't report font metrics.
+    pub(crate) fn font_extents(&self, options: &TextOptions) -> Option<(f32, f32)> {
+        let context = self.rendering_context()?;
+        context.save();
+        self.apply_font(&context, options);
+        let metrics = ExtendedTextMetrics::from(context.measure_text("").unwrap());
+        context.restore();
+        let (ascent, descent) = (
+            metrics.font_bounding_box_ascent(),
+            metrics.font_bounding_box_descent(),
+        );
+        (ascent.is_finite() && descent.is_finite()).then(|| {
+            (
+                self.pixels_to_length(ascent),
+                self.pixels_to_length(descent),
+            )
+        })
+    }
+
+    /// Draws each `(text, baseline_origin, angle)`, rotated clockwise by
+    /// `angle` radians around its baseline origin, in canvas coordinates.
+    pub(crate) fn render_glyphs(
+        &self,
+        glyphs: &[(&str, Point<f32, Scaled>, f32)],
+        options: &TextOptions,
+    ) {
+        if let Some(context) = self.rendering_context() {
+            context.save();
+            self.clip(&context);
+            self.apply_effects(&context);
+            self.apply_font(&context, options);
+            context.set_fill_style(&JsValue::from_str(&options.color.as_css_string()));
+            for (text, origin, angle) in glyphs {
+                let origin = origin.to_pixels(&self.scale).cast::<f64>();
+                context.save();
+                context.translate(origin.x, origin.y).unwrap();
+                context.rotate(f64::from(*angle)).unwrap();
+                context.fill_text(text, 0., 0.).unwrap();
+                context.restore();
+            }
+            context.restore();
+        }
+    }
+
+    pub(crate) fn draw_texture(&self, texture: &Texture, location: Point<f32, Scaled>) {
+        if let (Some(context), Some(element)) = (self.rendering_context(), texture_canvas(texture))
+        {
+            context.save();
+            self.clip(&context);
+            self.apply_effects(&context);
+            self.apply_image_smoothing(&context);
+
+            let location = location.to_pixels(&self.scale).cast::<f64>();
+            context
+                .draw_image_with_html_canvas_element(&element, location.x, location.y)
+                .unwrap();
+            context.restore();
+        }
     }
 }
 
+thread_local! {
+    /// The offscreen canvases holding each texture's pixels, along with the
+    /// version of the pixels they hold.
+    static TEXTURES: RefCell<HashMap<u64, (u64, HtmlCanvasElement)>> = RefCell::default();
+}
+
+/// Returns an offscreen canvas containing the current pixels of `texture`,
+/// uploading them if they've changed since the last time it was drawn.
+fn texture_canvas(texture: &Texture) -> Option<HtmlCanvasElement> {
+    let data = texture.data();
+    TEXTURES.with(|textures| {
+        let mut textures = textures.borrow_mut();
+        let element = match textures.get(&data.id) {
+            Some((version, element)) if *version == data.version => return Some(element.clone()),
+            Some((_, element)) => element.clone(),
+            None => create_element::<HtmlCanvasElement>("canvas"),
+        };
+
+        let size = data.pixels.size();
+        element.set_width(size.width);
+        element.set_height(size.height);
+        if size.width > 0 && size.height > 0 {
+            let context = element
+                .get_context("2d")
+                .ok()??
+                .dyn_into::<CanvasRenderingContext2d>()
+                .ok()?;
+            let image_data = ImageData::new_with_u8_clamped_array_and_sh(
+                Clamped(data.pixels.rgba()),
+                size.width,
+                size.height,
+            )
+            .ok()?;
+            context.put_image_data(&image_data, 0., 0.).ok()?;
+        }
+
+        textures.insert(data.id, (data.version, element.clone()));
+        Some(element)
+    })
+}
+
+/// Forgets the offscreen canvas of a texture that has been dropped.
+pub(crate) fn release_texture(id: u64) {
+    // The thread's textures may already have been destroyed if the texture is
+    // dropped while the thread exits.
+    let _ = TEXTURES.try_with(|textures| textures.borrow_mut().remove(&id));
+}
+
+/// Returns the preloaded `<img>` element for `image`.
+fn image_element(image: &Image) -> Option<HtmlImageElement> {
+    let element = window_document().get_element_by_id(&image.css_id()?)?;
+    Some(element.unchecked_into())
+}
+
 impl CanvasTransmogrifier {
-    fn redraw(&self, context: &mut TransmogrifierContext<'_, CanvasTransmogrifier, WebSys>) {
+    fn redraw(
+        &self,
+        invalidation: Invalidation,
+        context: &mut TransmogrifierContext<'_, CanvasTransmogrifier, WebSys>,
+    ) {
         let widget_context = Context::new(context.channels, context.frontend);
-        request_animation_frame(
-            widget_context,
-            context.state.redraw_already_requested.clone(),
-        );
+        context.state.frames.invalidate(invalidation);
+        request_animation_frame(widget_context, context.state.frames.clone());
     }
 }
 
-fn request_animation_frame(context: Context<Canvas>, already_requested: Arc<AtomicBool>) {
-    if !already_requested.fetch_or(true, Ordering::SeqCst) {
-        let cb = Closure::once_into_js(move || {
-            already_requested.store(false, Ordering::SeqCst);
-            draw_frame(context);
+/// The size of the canvas' default font, in pixels.
+const DEFAULT_FONT_SIZE: f64 = 10.;
+
+thread_local! {
+    /// Schedules the frames of every canvas on the page.
+    static SCHEDULER: RefCell<Scheduler> = RefCell::default();
+}
+
+/// Draws every canvas that needs a frame in a single `requestAnimationFrame`
+/// callback, and redraws the canvases whose size changes.
+#[derive(Default)]
+struct Scheduler {
+    /// The canvases waiting for the next frame.
+    pending: Vec<(Context<Canvas>, Arc<Frames>)>,
+    frame_requested: bool,
+    /// Every canvas that has been transmogrified. Canvases are forgotten once
+    /// their frames are dropped.
+    canvases: Vec<(Context<Canvas>, Weak<Frames>, HtmlCanvasElement)>,
+    /// Observes the size of every canvas element. Canvases are resized by
+    /// layout changes as well as by the window, so listening for the window's
+    /// `resize` event isn't enough.
+    resize_observer: Option<ResizeObserver>,
+}
+
+/// Adds a canvas to the scheduler, so that it is redrawn when its element
+/// changes size.
+fn register_canvas(context: Context<Canvas>, frames: &Arc<Frames>, element: &HtmlCanvasElement) {
+    SCHEDULER.with(|scheduler| {
+        let mut scheduler = scheduler.borrow_mut();
+        scheduler
+            .canvases
+            .push((context, Arc::downgrade(frames), element.clone()));
+        let observer = scheduler.resize_observer.get_or_insert_with(|| {
+            let onresize =
+                Closure::wrap(Box::new(canvases_resized) as Box<dyn Fn()>).into_js_value();
+            ResizeObserver::new(&Function::from(onresize)).unwrap()
         });
-        web_sys::window()
-            .unwrap()
-            .request_animation_frame(cb.dyn_ref().unwrap())
-            .unwrap();
+        observer.observe(element);
+    });
+}
+
+/// Redraws the canvases whose size changed.
+fn canvases_resized() {
+    let canvases = SCHEDULER.with(|scheduler| {
+        let mut scheduler = scheduler.borrow_mut();
+        let Scheduler {
+            canvases,
+            resize_observer,
+            ..
+        } = &mut *scheduler;
+        canvases.retain(|(_, frames, element)| {
+            let alive = frames.strong_count() > 0;
+            if !alive {
+                if let Some(observer) = resize_observer.as_ref() {
+                    observer.unobserve(element);
+                }
+            }
+            alive
+        });
+        canvases
+            .iter()
+            .filter_map(|(context, frames, _)| Some((context.clone(), frames.upgrade()?)))
+            .collect::<Vec<_>>()
+    });
+
+    let scale = display_scale();
+    for (context, frames) in canvases {
+        let size = measure(&context).map(|size| size.cast::<f32>().to_scaled(&scale));
+        if size.is_some() && *frames.size.lock().unwrap() != size {
+            frames.invalidate(Invalidation::Everything);
+            request_animation_frame(context, frames);
+        }
     }
 }
 
-fn draw_frame(context: Context<Canvas>) {
-    context.map_mut(|canvas, context| {
+fn request_animation_frame(context: Context<Canvas>, frames: Arc<Frames>) {
+    if frames
+        .redraw_already_requested
+        .fetch_or(true, Ordering::SeqCst)
+    {
+        frames.statistics.record_coalesced_redraw();
+        return;
+    }
+
+    SCHEDULER.with(|scheduler| {
+        let mut scheduler = scheduler.borrow_mut();
+        scheduler.pending.push((context, frames));
+        if !scheduler.frame_requested {
+            scheduler.frame_requested = true;
+            let cb = Closure::once_into_js(draw_frames);
+            web_sys::window()
+                .unwrap()
+                .request_animation_frame(cb.dyn_ref().unwrap())
+                .unwrap();
+        }
+    });
+}
+
+/// Draws every pending canvas.
+fn draw_frames(timestamp: f64) {
+    let pending = SCHEDULER.with(|scheduler| {
+        let mut scheduler = scheduler.borrow_mut();
+        scheduler.frame_requested = false;
+        std::mem::take(&mut scheduler.pending)
+    });
+
+    // Resizing a canvas can change the layout of its siblings, so every
+    // canvas is measured before any of them are resized.
+    let measured = pending
+        .into_iter()
+        .map(|(context, frames)| {
+            frames
+                .redraw_already_requested
+                .store(false, Ordering::SeqCst);
+            let size = measure(&context);
+            (context, frames, size)
+        })
+        .collect::<Vec<_>>();
+    for (context, frames, size) in measured {
+        if let Some(size) = size {
+            draw_frame(context, frames, timestamp, size);
+        }
+    }
+}
+
+/// Returns the size of the canvas' element, in pixels.
+fn measure(context: &Context<Canvas>) -> Option<Size<u32, Pixels>> {
+    let element = context
+        .map_mut(|_, context| canvas_element(context.widget().registration()?.id()))
+        .flatten()?;
+    // The bounding rectangle isn't rounded like `client_width()`, so canvases
+    // that split a fractional width never add up to more than their container.
+    let bounds = element.get_bounding_client_rect();
+    Some(Size::new(
+        bounds.width().max(0.).floor() as u32,
+        bounds.height().max(0.).floor() as u32,
+    ))
+}
+
+fn display_scale() -> DisplayScale<f32> {
+    DisplayScale::new(
+        Scale::new(web_sys::window().unwrap().device_pixel_ratio() as f32),
+        Scale::new(1.),
+    )
+}
+
+fn draw_frame(
+    context: Context<Canvas>,
+    frames: Arc<Frames>,
+    timestamp: f64,
+    pixel_size: Size<u32, Pixels>,
+) {
+    let captures = std::mem::take(&mut *frames.captures.lock().unwrap());
+    let drawn = context.map_mut(|canvas, context| {
         let widget = context.widget().registration().unwrap().id().clone();
         if let Some(canvas_element) = canvas_element(&widget) {
-            let scale = DisplayScale::new(
-                Scale::new(web_sys::window().unwrap().device_pixel_ratio() as f32),
-                Scale::new(1.),
-            );
+            let scale = display_scale();
+            let size = pixel_size.cast::<f32>().to_scaled(&scale);
+
+            let mut invalidation = frames
+                .invalidated
+                .lock()
+                .unwrap()
+                .take()
+                .unwrap_or(Invalidation::Everything);
+            {
+                let mut last_size = frames.size.lock().unwrap();
+                if *last_size != Some(size) {
+                    // Resizing the element resets its contents.
+                    canvas_element.set_width(pixel_size.width);
+                    canvas_element.set_height(pixel_size.height);
+                    invalidation = Invalidation::Everything;
+                    *last_size = Some(size);
+                    canvas.renderable.resized(size);
+                }
+            }
+            if canvas.show_statistics {
+                // The overlay is redrawn with updated statistics every frame.
+                invalidation = Invalidation::Everything;
+            }
+
+            let elapsed = frames
+                .last_frame
+                .lock()
+                .unwrap()
+                .take()
+                .map_or_else(Duration::default, |last_frame| {
+                    Duration::from_secs_f64((timestamp - last_frame).max(0.) / 1000.)
+                });
+            canvas.poll_gestures(Duration::from_secs_f64(timestamp / 1000.));
+            // Keep drawing frames while waiting to recognize a long press.
+            let needs_frame = canvas.renderable.update(elapsed) || canvas.gestures.is_pending();
+            let since_last_frame =
+                frames
+                    .last_drawn
+                    .lock()
+                    .unwrap()
+                    .replace(timestamp)
+                    .map(|last_drawn| {
+                        Duration::from_secs_f64((timestamp - last_drawn).max(0.) / 1000.)
+                    });
+
+            let rendering_context = canvas_element
+                .get_context("2d")
+                .ok()
+                .flatten()
+                .and_then(|c| c.dyn_into::<CanvasRenderingContext2d>().ok());
+            if let Some(rendering_context) = &rendering_context {
+                // Keep the pixels outside of the dirty region, and limit all
+                // drawing to it.
+                let dirty = invalidation
+                    .region()
+                    .unwrap_or_else(|| Rect::from(SizedRect::from(size)))
+                    .to_pixels(&scale)
+                    .cast::<f64>()
+                    .as_sized();
+                rendering_context.save();
+                rendering_context.begin_path();
+                rendering_context.rect(
+                    dirty.origin.x,
+                    dirty.origin.y,
+                    dirty.size.width,
+                    dirty.size.height,
+                );
+                rendering_context.clip();
+                rendering_context.clear_rect(
+                    dirty.origin.x,
+                    dirty.origin.y,
+                    dirty.size.width,
+                    dirty.size.height,
+                );
+            }
 
-            let size = Size::<_, Pixels>::new(
-                canvas_element.client_width(),
-                canvas_element.client_height(),
-            )
-            .max(&Size::default())
-            .cast::<u32>();
-            canvas_element.set_width(size.width);
-            canvas_element.set_height(size.height);
-            let size = size.cast::<f32>().to_scaled(&scale);
             let renderer = BrowserRenderer {
                 widget,
                 clip: SizedRect::from(size.cast::<f64>()),
+                shapes: Vec::new(),
+                effects: Effects::default(),
+                stroke_style: StrokeStyle::default(),
+                image_smoothing: ImageSmoothing::default(),
                 theme: context.frontend().theme(),
                 scale,
             };
-            canvas.renderable.render(
-                CanvasRenderer::BrowserRenderer(renderer),
+            let performance = web_sys::window().unwrap().performance();
+            canvas.render_frame(
+                CanvasRenderer::new(
+                    CanvasBackend::BrowserRenderer(renderer),
+                    invalidation.region(),
+                ),
                 &ContentArea {
                     size: ContentSize {
                         content: size,
@@ -117,9 +689,132 @@ fn draw_frame(context: Context<Canvas>) {
                     },
                     location: Point::default(),
                 },
+                since_last_frame,
+                || {
+                    performance
+                        .as_ref()
+                        .map_or_else(Duration::default, |performance| {
+                            Duration::from_secs_f64(performance.now() / 1000.)
+                        })
+                },
             );
+
+            let accessibility = canvas.accessibility();
+            {
+                let mut emitted = frames.accessibility.lock().unwrap();
+                if *emitted != accessibility {
+                    update_fallback(&canvas_element, accessibility.as_ref());
+                    *emitted = accessibility.clone();
+                }
+            }
+
+            update_cursor(&canvas_element, &frames, canvas);
+
+            let mut captured = None;
+            if let Some(rendering_context) = &rendering_context {
+                rendering_context.restore();
+                if let Some(accessibility) = &accessibility {
+                    draw_focus(rendering_context, &canvas_element, accessibility, &scale);
+                }
+                if !captures.is_empty() {
+                    captured = capture(rendering_context, pixel_size);
+                }
+            }
+
+            if needs_frame {
+                *frames.last_frame.lock().unwrap() = Some(timestamp);
+                frames.invalidate(Invalidation::Everything);
+            }
+            (needs_frame, captured)
+        } else {
+            (false, None)
         }
     });
+    let (needs_frame, captured) = drawn.unwrap_or_default();
+
+    for callback in captures {
+        callback.invoke(captured.clone());
+    }
+
+    if needs_frame {
+        request_animation_frame(context, frames);
+    }
+}
+
+/// Shows the cursor for the pointer's last location over the canvas.
+fn update_cursor(element: &HtmlCanvasElement, frames: &Frames, canvas: &Canvas) {
+    let location = match *frames.pointer_location.lock().unwrap() {
+        Some(location) => location,
+        None => return,
+    };
+    let cursor = canvas.cursor_at(location);
+    let mut shown = frames.cursor.lock().unwrap();
+    if *shown != Some(cursor) {
+        element
+            .style()
+            .set_property("cursor", cursor.css_name())
+            .unwrap();
+        *shown = Some(cursor);
+    }
+}
+
+/// Replaces the fallback content of `element`, which assistive technology
+/// presents in place of the canvas' pixels.
+fn update_fallback(element: &HtmlCanvasElement, accessibility: Option<&AccessibilityTree>) {
+    element.set_inner_html("");
+    if let Some(accessibility) = accessibility {
+        element
+            .set_attribute("role", accessibility.role.aria_role())
+            .unwrap();
+        element
+            .set_attribute("aria-label", &accessibility.label)
+            .unwrap();
+        for region in &accessibility.regions {
+            let button = create_element::<HtmlElement>("button");
+            button.set_attribute("type", "button").unwrap();
+            button.set_text_content(Some(&region.name));
+            element.append_child(&button).unwrap();
+        }
+    } else {
+        element.remove_attribute("role").unwrap();
+        element.remove_attribute("aria-label").unwrap();
+    }
+}
+
+/// Tells the browser where each region of the fallback content is drawn. The
+/// browser draws a focus ring around the focused region, and reports its
+/// bounds to screen magnifiers.
+fn draw_focus(
+    context: &CanvasRenderingContext2d,
+    element: &HtmlCanvasElement,
+    accessibility: &AccessibilityTree,
+    scale: &DisplayScale<f32>,
+) {
+    let children = element.children();
+    for (index, region) in accessibility.regions.iter().enumerate() {
+        if let Some(child) = children.item(index as u32) {
+            let bounds = region.bounds.to_pixels(scale).cast::<f64>().as_sized();
+            context.begin_path();
+            context.rect(
+                bounds.origin.x,
+                bounds.origin.y,
+                bounds.size.width,
+                bounds.size.height,
+            );
+            context.draw_focus_if_needed(&child);
+        }
+    }
+}
+
+/// Reads the pixels of the canvas.
+fn capture(context: &CanvasRenderingContext2d, size: Size<u32, Pixels>) -> Option<CanvasImage> {
+    if size.width == 0 || size.height == 0 {
+        return CanvasImage::new(size, Vec::new());
+    }
+    let image_data = context
+        .get_image_data(0., 0., f64::from(size.width), f64::from(size.height))
+        .ok()?;
+    CanvasImage::new(size, image_data.data().0)
 }
 
 impl gooey::core::Transmogrifier<WebSys> for CanvasTransmogrifier {
@@ -131,8 +826,45 @@ impl gooey::core::Transmogrifier<WebSys> for CanvasTransmogrifier {
         command: Command,
         context: &mut TransmogrifierContext<'_, Self, WebSys>,
     ) {
-        let Command::Refresh = command;
-        self.redraw(context);
+        let invalidation = match command {
+            Command::Refresh => Invalidation::Everything,
+            Command::Invalidate(region) => Invalidation::Region(region),
+            Command::Message(message) => {
+                context.widget.renderable.receive(message);
+                Invalidation::Everything
+            }
+            Command::ShowStatistics(show) => {
+                context.widget.show_statistics = show;
+                Invalidation::Everything
+            }
+            Command::Capture(callback) => {
+                context.state.frames.captures.lock().unwrap().push(callback);
+                Invalidation::Everything
+            }
+            Command::SetCursor(cursor) => {
+                // The cursor is updated once the frame is drawn, in case the
+                // frame sets its own.
+                context.widget.cursor = cursor;
+                Invalidation::Everything
+            }
+            Command::Drag(event) => {
+                if !context.widget.handle_drag(event) {
+                    return;
+                }
+                Invalidation::Everything
+            }
+            Command::RegisterFont(font) => {
+                // The canvas is redrawn once the font has loaded.
+                load_font(
+                    &font,
+                    Context::new(context.channels, context.frontend),
+                    context.state.frames.clone(),
+                );
+                context.widget.fonts.push(font);
+                return;
+            }
+        };
+        self.redraw(invalidation, context);
     }
 }
 
@@ -146,29 +878,320 @@ impl WebSysTransmogrifier for CanvasTransmogrifier {
             .initialize_widget_element(&element, &context)
             .unwrap_or_default();
         context.state.css = Some(css);
+        context.state.frames = Arc::new(Frames {
+            statistics: context.widget.statistics.clone(),
+            ..Frames::default()
+        });
 
-        // Setup a refresh-on-resize callback.
         let widget_context = Context::from(&context);
-        let already_requested = context.state.redraw_already_requested.clone();
-        let onresize = Closure::wrap(Box::new(move || {
-            request_animation_frame(widget_context.clone(), already_requested.clone());
+        context.widget.renderable.initialize(&widget_context);
+        for font in &context.widget.fonts {
+            load_font(font, widget_context.clone(), context.state.frames.clone());
+        }
+
+        // Describe the canvas to assistive technology.
+        let accessibility = context.widget.accessibility();
+        update_fallback(&element, accessibility.as_ref());
+        *context.state.frames.accessibility.lock().unwrap() = accessibility;
+
+        // Forward pointer input to the canvas.
+        let frames = context.state.frames.clone();
+        let pointer_context = widget_context.clone();
+        let pointer_element = element.clone();
+        let onpointer = Closure::wrap(Box::new(move |event: web_sys::Event| {
+            if let Some(pointer) = pointer_event(&event) {
+                if let PointerEventKind::Down(_) = pointer.kind {
+                    // Keep receiving the pointer's events until it's released,
+                    // even outside of the canvas. The browser releases the
+                    // capture when the pointer is released.
+                    if let Err(err) = pointer_element.set_pointer_capture(pointer.pointer_id as i32)
+                    {
+                        log::warn!("error capturing pointer: {:?}", err);
+                    }
+                }
+                *frames.pointer_location.lock().unwrap() = Some(pointer.location);
+                let redraw = pointer_context
+                    .map_mut(|canvas, _| {
+                        let redraw = canvas.handle_pointer(
+                            pointer,
+                            Duration::from_secs_f64(event.time_stamp() / 1000.),
+                        );
+                        update_cursor(&pointer_element, &frames, canvas);
+                        redraw
+                    })
+                    .unwrap_or_default();
+                if redraw {
+                    event.prevent_default();
+                    frames.invalidate(Invalidation::Everything);
+                    request_animation_frame(pointer_context.clone(), frames.clone());
+                }
+            }
+        }) as Box<dyn Fn(web_sys::Event)>)
+        .into_js_value();
+        for event in [
+            "pointerdown",
+            "pointermove",
+            "pointerup",
+            "pointercancel",
+            "wheel",
+        ] {
+            element
+                .add_event_listener_with_callback(event, &Function::from(onpointer.clone()))
+                .unwrap();
+        }
+
+        // Accept drops, and deliver them to the canvas.
+        let frames = context.state.frames.clone();
+        let drag_context = widget_context.clone();
+        let ondrag = Closure::wrap(Box::new(move |event: web_sys::DragEvent| {
+            // Dropping is only allowed if the browser's default handling,
+            // such as opening a dropped file, is prevented.
+            event.prevent_default();
+            let context = drag_context.clone();
+            let frames = frames.clone();
+            let deliver = move |drag: DragEvent| {
+                let redraw = context
+                    .map_mut(|canvas, _| canvas.handle_drag(drag))
+                    .unwrap_or_default();
+                if redraw {
+                    frames.invalidate(Invalidation::Everything);
+                    request_animation_frame(context, frames);
+                }
+            };
+            let location = Point::new(event.offset_x() as f32, event.offset_y() as f32);
+            let kind = match event.type_().as_str() {
+                "dragenter" => DragEventKind::Enter,
+                "dragover" => DragEventKind::Over,
+                "dragleave" => DragEventKind::Leave,
+                "drop" => {
+                    read_drop(&event, move |payload| {
+                        deliver(DragEvent {
+                            kind: DragEventKind::Drop(payload),
+                            location,
+                        });
+                    });
+                    return;
+                }
+                _ => return,
+            };
+            deliver(DragEvent { kind, location });
+        }) as Box<dyn Fn(web_sys::DragEvent)>)
+        .into_js_value();
+        for event in ["dragenter", "dragover", "dragleave", "drop"] {
+            element
+                .add_event_listener_with_callback(event, &Function::from(ondrag.clone()))
+                .unwrap();
+        }
+
+        // Focus rings around regions are drawn with the frame, so moving the
+        // focus requires a new frame.
+        let frames = context.state.frames.clone();
+        let focus_context = widget_context.clone();
+        let onfocus = Closure::wrap(Box::new(move || {
+            frames.invalidate(Invalidation::Everything);
+            request_animation_frame(focus_context.clone(), frames.clone());
         }) as Box<dyn Fn()>)
         .into_js_value();
-        web_sys::window()
-            .unwrap()
-            .add_event_listener_with_callback("resize", &Function::from(onresize))
-            .unwrap();
+        for event in ["focusin", "focusout"] {
+            element
+                .add_event_listener_with_callback(event, &Function::from(onfocus.clone()))
+                .unwrap();
+        }
+
+        // Redraw when the canvas' size changes.
+        register_canvas(widget_context, &context.state.frames, &element);
 
         // Initialize the canvas by drawing a frame.
-        self.redraw(&mut context);
+        self.redraw(Invalidation::Everything, &mut context);
 
         Some(element.unchecked_into())
     }
 
     fn convert_style_to_css(&self, style: &Style, css: CssBlockBuilder) -> CssBlockBuilder {
         self.convert_standard_components_to_css(style, css)
+            // Block layout avoids the gap below inline elements, which would
+            // make canvases sharing a container overflow it.
+            .with_css_statement("display: block")
             .with_css_statement("width: 100%")
             .with_css_statement("height: 100%")
+            // Deliver touches as pointer events instead of scrolling the page.
+            .with_css_statement("touch-action: none")
+    }
+}
+
+/// Reads the payload of a drop, and invokes `dropped` with it. Files are read
+/// asynchronously.
+fn read_drop<F: FnOnce(DragPayload) + 'static>(event: &web_sys::DragEvent, dropped: F) {
+    let data_transfer = match event.data_transfer() {
+        Some(data_transfer) => data_transfer,
+        None => return,
+    };
+
+    let files = data_transfer
+        .files()
+        .map(|files| {
+            (0..files.length())
+                .filter_map(|index| files.get(index))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    if files.is_empty() {
+        if let Ok(text) = data_transfer.get_data("text/plain") {
+            dropped(DragPayload::Text(text));
+        }
+        return;
+    }
+
+    wasm_bindgen_futures::spawn_local(async move {
+        let mut dropped_files = Vec::with_capacity(files.len());
+        for file in files {
+            match JsFuture::from(file.array_buffer()).await {
+                Ok(buffer) => dropped_files.push(DroppedFile {
+                    name: file.name(),
+                    bytes: Uint8Array::new(&buffer).to_vec(),
+                }),
+                Err(err) => log::error!("error reading dropped file {}: {:?}", file.name(), err),
+            }
+        }
+        dropped(DragPayload::Files(dropped_files));
+    });
+}
+
+thread_local! {
+    /// The font families added to the document, along with the canvases to
+    /// redraw once each is ready.
+    static FONTS: RefCell<HashMap<String, FontState>> = RefCell::default();
+}
+
+enum FontState {
+    Loading(Vec<(Context<Canvas>, Arc<Frames>)>),
+    Loaded,
+}
+
+/// Adds `font` to the document, and redraws the canvas once it's ready. Each
+/// family is only added once, however many canvases register it.
+fn load_font(font: &CanvasFont, context: Context<Canvas>, frames: Arc<Frames>) {
+    let family = font.family().to_string();
+    let already_added = FONTS.with(|fonts| match fonts.borrow_mut().get_mut(&family) {
+        Some(FontState::Loading(waiting)) => {
+            waiting.push((context.clone(), frames.clone()));
+            true
+        }
+        Some(FontState::Loaded) => true,
+        None => false,
+    });
+    if already_added {
+        return;
+    }
+
+    let face = match font.source() {
+        FontSource::Bytes(bytes) => {
+            FontFace::new_with_array_buffer(&family, &Uint8Array::from(bytes.as_slice()).buffer())
+        }
+        FontSource::Url(url) => {
+            FontFace::new_with_str(&family, &format!("url(\"{}\")", url.replace('"', "%22")))
+        }
+    };
+    let face = match face {
+        Ok(face) => face,
+        Err(err) => {
+            log::error!("error creating font {}: {:?}", family, err);
+            return;
+        }
+    };
+    FONTS.with(|fonts| {
+        fonts
+            .borrow_mut()
+            .insert(family.clone(), FontState::Loading(vec![(context, frames)]))
+    });
+    wasm_bindgen_futures::spawn_local(async move {
+        let loaded = match face.load() {
+            Ok(promise) => JsFuture::from(promise).await,
+            Err(err) => Err(err),
+        };
+        let added = loaded.and_then(|_| {
+            let document = web_sys::window().unwrap().document().unwrap();
+            document.fonts().add(&face)
+        });
+        let state = FONTS.with(|fonts| {
+            let mut fonts = fonts.borrow_mut();
+            if added.is_ok() {
+                fonts.insert(family.clone(), FontState::Loaded)
+            } else {
+                // Let a later registration try again.
+                fonts.remove(&family)
+            }
+        });
+        match added {
+            Ok(_) => {
+                if let Some(FontState::Loading(waiting)) = state {
+                    for (context, frames) in waiting {
+                        frames.invalidate(Invalidation::Everything);
+                        request_animation_frame(context, frames);
+                    }
+                }
+            }
+            Err(err) => log::error!("error loading font {}: {:?}", family, err),
+        }
+    });
+}
+
+/// Converts a DOM pointer or wheel event into a [`PointerEvent`].
+fn pointer_event(event: &web_sys::Event) -> Option<PointerEvent> {
+    let mouse_event = event.dyn_ref::<MouseEvent>()?;
+    let location = Point::new(mouse_event.offset_x() as f32, mouse_event.offset_y() as f32);
+    let kind = match event.type_().as_str() {
+        "pointerdown" => PointerEventKind::Down(pointer_button(mouse_event.button())),
+        "pointermove" => PointerEventKind::Move,
+        "pointerup" => PointerEventKind::Up(pointer_button(mouse_event.button())),
+        "pointercancel" => PointerEventKind::Cancel,
+        "wheel" => {
+            let wheel_event = event.dyn_ref::<WheelEvent>()?;
+            // Convert line and page based deltas to an approximate distance.
+            let multiplier = match wheel_event.delta_mode() {
+                WheelEvent::DOM_DELTA_LINE => 16.,
+                WheelEvent::DOM_DELTA_PAGE => 800.,
+                _ => 1.,
+            };
+            PointerEventKind::Wheel(Vector::new(
+                (wheel_event.delta_x() * multiplier) as f32,
+                (wheel_event.delta_y() * multiplier) as f32,
+            ))
+        }
+        _ => return None,
+    };
+    // Wheel events aren't pointer events, and are always from the mouse.
+    let (pointer_id, device) = event.dyn_ref::<web_sys::PointerEvent>().map_or(
+        (0, PointerDevice::Mouse),
+        |pointer_event| {
+            let device = match pointer_event.pointer_type().as_str() {
+                "touch" => PointerDevice::Touch,
+                "pen" => PointerDevice::Pen,
+                _ => PointerDevice::Mouse,
+            };
+            (pointer_event.pointer_id() as u32, device)
+        },
+    );
+    Some(PointerEvent {
+        kind,
+        location,
+        pointer_id,
+        device,
+        modifiers: Modifiers {
+            shift: mouse_event.shift_key(),
+            control: mouse_event.ctrl_key(),
+            alt: mouse_event.alt_key(),
+            meta: mouse_event.meta_key(),
+        },
+    })
+}
+
+fn pointer_button(button: i16) -> PointerButton {
+    match button {
+        0 => PointerButton::Primary,
+        1 => PointerButton::Middle,
+        2 => PointerButton::Secondary,
+        other => PointerButton::Other(other as u16),
     }
 }
 
@@ -179,10 +1202,15 @@ impl WebSysTransmogrifier for CanvasTransmogrifier {
 /// The renderer uses
 /// [`Window::device_pixel_ratio()`](web_sys::Window::device_pixel_ratio) to
 /// scale between [`Points`] and [`Pixels`].
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BrowserRenderer {
     widget: WidgetId,
     clip: SizedRect<f64, Scaled>,
+    /// Non-rectangular clips, intersected with `clip`.
+    shapes: Vec<ClipShape>,
+    effects: Effects,
+    stroke_style: StrokeStyle,
+    image_smoothing: ImageSmoothing,
     theme: SystemTheme,
     scale: DisplayScale<f32>,
 }
@@ -204,6 +1232,10 @@ impl Renderer for BrowserRenderer {
                 .intersection(&bounds.cast())
                 .unwrap_or_default()
                 .as_sized(),
+            shapes: self.shapes.clone(),
+            effects: self.effects,
+            stroke_style: self.stroke_style.clone(),
+            image_smoothing: self.image_smoothing,
             theme: self.theme,
             scale: self.scale,
         }
@@ -227,6 +1259,8 @@ impl Renderer for BrowserRenderer {
             let baseline_origin = baseline_origin.to_pixels(&self.scale);
             context.save();
             self.clip(&context);
+            self.apply_effects(&context);
+            self.apply_font(&context, options);
             context.set_fill_style(&JsValue::from_str(&options.color.as_css_string()));
             context
                 .fill_text(text, baseline_origin.x as f64, baseline_origin.y as f64)
@@ -235,14 +1269,16 @@ impl Renderer for BrowserRenderer {
         }
     }
 
-    fn measure_text(&self, text: &str, _options: &TextOptions) -> TextMetrics<Scaled> {
+    fn measure_text(&self, text: &str, options: &TextOptions) -> TextMetrics<Scaled> {
         if let Some(context) = self.rendering_context() {
-            // TODO handle text options
+            context.save();
+            self.apply_font(&context, options);
             let metrics = ExtendedTextMetrics::from(context.measure_text(text).unwrap());
+            context.restore();
             TextMetrics {
-                width: Figure::new(metrics.width() as f32),
-                ascent: Figure::new(metrics.actual_bounding_box_ascent() as f32),
-                descent: Figure::new(metrics.actual_bounding_box_descent() as f32),
+                width: Figure::new(self.pixels_to_length(metrics.width())),
+                ascent: Figure::new(self.pixels_to_length(metrics.actual_bounding_box_ascent())),
+                descent: Figure::new(self.pixels_to_length(metrics.actual_bounding_box_descent())),
                 line_gap: Figure::default(),
             }
         } else {
@@ -259,9 +1295,7 @@ impl Renderer for BrowserRenderer {
             let rect = rect.to_pixels(&self.scale);
             context.save();
             self.clip(&context);
-            // TODO handle line width
-            context.set_stroke_style(&JsValue::from_str(&options.color.as_css_string()));
-            context.set_line_width(options.line_width.get() as f64);
+            self.apply_stroke(&context, options);
             let rect = rect.cast::<f64>().as_sized();
             context.stroke_rect(
                 rect.origin.x,
@@ -277,6 +1311,7 @@ impl Renderer for BrowserRenderer {
         if let Some(context) = self.rendering_context() {
             context.save();
             self.clip(&context);
+            self.apply_effects(&context);
             context.set_fill_style(&JsValue::from_str(&color.as_css_string()));
             let rect = rect.to_pixels(&self.scale).cast::<f64>().as_sized();
             context.fill_rect(
@@ -298,8 +1333,7 @@ impl Renderer for BrowserRenderer {
         if let Some(context) = self.rendering_context() {
             context.save();
             self.clip(&context);
-            // TODO handle line width
-            context.set_stroke_style(&JsValue::from_str(&options.color.as_css_string()));
+            self.apply_stroke(&context, options);
             context.begin_path();
             let point_a = point_a.to_pixels(&self.scale).cast::<f64>();
             context.move_to(point_a.x, point_a.y);
@@ -315,20 +1349,17 @@ impl Renderer for BrowserRenderer {
         image: &Image,
         location: impl Displayable<f32, Pixels = Point<f32, Pixels>>,
     ) {
-        if let Some(context) = self.rendering_context() {
-            if let Some(css_id) = image.css_id() {
-                if let Some(element) = window_document().get_element_by_id(&css_id) {
-                    let element = element.unchecked_into::<HtmlImageElement>();
-                    context.save();
-                    self.clip(&context);
-
-                    let location = location.to_pixels(&self.scale).cast::<f64>();
-                    context
-                        .draw_image_with_html_image_element(&element, location.x, location.y)
-                        .unwrap();
-                    context.restore();
-                }
-            }
+        if let (Some(context), Some(element)) = (self.rendering_context(), image_element(image)) {
+            context.save();
+            self.clip(&context);
+            self.apply_effects(&context);
+            self.apply_image_smoothing(&context);
+
+            let location = location.to_pixels(&self.scale).cast::<f64>();
+            context
+                .draw_image_with_html_image_element(&element, location.x, location.y)
+                .unwrap();
+            context.restore();
         }
     }
 }
@@ -357,6 +1388,12 @@ extern "C" {
     #[wasm_bindgen(method, getter, js_name = actualBoundingBoxRight)]
     pub fn actual_bounding_box_right(this: &ExtendedTextMetrics) -> f64;
 
+    #[wasm_bindgen(method, getter, js_name = fontBoundingBoxAscent)]
+    pub fn font_bounding_box_ascent(this: &ExtendedTextMetrics) -> f64;
+
+    #[wasm_bindgen(method, getter, js_name = fontBoundingBoxDescent)]
+    pub fn font_bounding_box_descent(this: &ExtendedTextMetrics) -> f64;
+
     #[wasm_bindgen(method, getter)]
     pub fn width(this: &ExtendedTextMetrics) -> f64;
 }
@@ -375,6 +1412,39 @@ impl ExtendedTextMetrics {
 
 #[derive(Debug, Default)]
 pub struct State {
-    redraw_already_requested: Arc<AtomicBool>,
+    frames: Arc<Frames>,
     css: Option<CssRules>,
 }
+
+/// Frame bookkeeping shared with the `requestAnimationFrame` callbacks.
+#[derive(Debug, Default)]
+struct Frames {
+    redraw_already_requested: AtomicBool,
+    size: Mutex<Option<Size<f32, Scaled>>>,
+    /// The region accumulated since the last frame was drawn.
+    invalidated: Mutex<Option<Invalidation>>,
+    /// The timestamp of the previous frame, if it requested another frame.
+    last_frame: Mutex<Option<f64>>,
+    /// The timestamp of the previous frame.
+    last_drawn: Mutex<Option<f64>>,
+    statistics: CanvasStatistics,
+    /// Callbacks waiting for the next frame to be captured.
+    captures: Mutex<Vec<Callback<Option<CanvasImage>>>>,
+    /// The accessibility tree currently emitted as fallback content.
+    accessibility: Mutex<Option<AccessibilityTree>>,
+    /// The most recent pointer location, relative to the canvas.
+    pointer_location: Mutex<Option<Point<f32, Scaled>>>,
+    /// The cursor currently shown over the canvas.
+    cursor: Mutex<Option<CursorIcon>>,
+}
+
+impl Frames {
+    fn invalidate(&self, invalidation: Invalidation) {
+        let mut invalidated = self.invalidated.lock().unwrap();
+        *invalidated = Some(
+            invalidated
+                .take()
+                .map_or(invalidation, |existing| existing.union(invalidation)),
+        );
+    }
+}
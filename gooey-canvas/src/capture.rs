@@ -0,0 +1,61 @@
+use gooey::core::{
+    figures::{Point, Size},
+    Pixels,
+};
+
+/// The pixels of a [`Canvas`](crate::Canvas), captured with
+/// [`Command::Capture`](crate::Command::Capture).
+///
+/// Pixels are stored row by row, top to bottom, as 8-bit RGBA values that are
+/// not premultiplied. The buffer can be passed directly to
+/// `image::RgbaImage::from_raw()` to save it as a PNG.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanvasImage {
+    size: Size<u32, Pixels>,
+    rgba: Vec<u8>,
+}
+
+impl CanvasImage {
+    /// Returns an image of `size` containing `rgba`. Returns `None` if `rgba`
+    /// doesn't contain exactly four bytes per pixel.
+    #[must_use]
+    pub fn new(size: Size<u32, Pixels>, rgba: Vec<u8>) -> Option<Self> {
+        if rgba.len() == size.width as usize * size.height as usize * 4 {
+            Some(Self { size, rgba })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the size of the image.
+    #[must_use]
+    pub const fn size(&self) -> Size<u32, Pixels> {
+        self.size
+    }
+
+    /// Returns the RGBA values of every pixel.
+    #[must_use]
+    pub fn rgba(&self) -> &[u8] {
+        &self.rgba
+    }
+
+    /// Returns the RGBA values of every pixel.
+    #[must_use]
+    pub fn into_rgba(self) -> Vec<u8> {
+        self.rgba
+    }
+
+    /// Returns the RGBA value of the pixel at `location`, or `None` if it is
+    /// outside of the image.
+    #[must_use]
+    pub fn pixel(&self, location: Point<u32, Pixels>) -> Option<[u8; 4]> {
+        if location.x < self.size.width && location.y < self.size.height {
+            let offset = (location.y as usize * self.size.width as usize + location.x as usize) * 4;
+            let mut pixel = [0; 4];
+            pixel.copy_from_slice(&self.rgba[offset..offset + 4]);
+            Some(pixel)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,588 @@
+use std::any::Any;
+
+use gooey::{
+    core::{
+        figures::{ExtentsRect, Figure, Point, Rect, Rectlike, Size, SizedRect},
+        styles::{Color, SystemTheme},
+        Scaled,
+    },
+    frontends::rasterizer::ContentArea,
+    renderer::{Renderer, StrokeOptions, TextOptions},
+};
+
+use crate::{CanvasRenderer, Path, Renderable};
+
+/// Colors assigned to series that don't specify one.
+const PALETTE: [Color; 6] = [
+    Color::STEELBLUE,
+    Color::DARKORANGE,
+    Color::SEAGREEN,
+    Color::CRIMSON,
+    Color::MEDIUMPURPLE,
+    Color::GOLDENROD,
+];
+
+/// Space between the chart's elements.
+const SPACING: f32 = 8.;
+
+/// The way a [`Chart`] presents its series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartKind {
+    /// Points connected by lines.
+    Line,
+    /// Bars rising from zero, grouped by x value.
+    Bar,
+    /// Unconnected points.
+    Scatter,
+    /// Lines with the area between them and zero filled. Series are drawn in
+    /// order, so translucent colors are recommended when areas overlap.
+    Area,
+}
+
+/// A named set of data points.
+#[derive(Debug, Clone)]
+pub struct Series {
+    /// The name shown in the legend.
+    pub name: String,
+    /// The color of the series. When `None`, a color is picked from a default
+    /// palette.
+    pub color: Option<Color>,
+    /// The data points, as `(x, y)` pairs.
+    pub points: Vec<(f32, f32)>,
+}
+
+impl Series {
+    /// Returns a new series.
+    pub fn new(name: impl Into<String>, points: impl IntoIterator<Item = (f32, f32)>) -> Self {
+        Self {
+            name: name.into(),
+            color: None,
+            points: points.into_iter().collect(),
+        }
+    }
+
+    /// Sets the color and returns self.
+    #[must_use]
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+}
+
+/// A chart axis.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Axis {
+    /// The range shown on this axis. When `None`, the range is computed from
+    /// the data.
+    pub range: Option<(f32, f32)>,
+    /// The approximate number of ticks to generate.
+    pub ticks: usize,
+}
+
+impl Default for Axis {
+    fn default() -> Self {
+        Self {
+            range: None,
+            ticks: 5,
+        }
+    }
+}
+
+/// A message that updates a [`Chart`], sent with
+/// [`Command::message()`](crate::Command::message).
+#[derive(Debug, Clone)]
+pub enum ChartCommand {
+    /// Replaces all series.
+    SetSeries(Vec<Series>),
+    /// Appends a point to the series at an index.
+    Push {
+        /// The index of the series.
+        series: usize,
+        /// The `(x, y)` point to add.
+        point: (f32, f32),
+    },
+}
+
+/// A [`Renderable`] that draws a line, bar, scatter or area chart, with axes
+/// and a legend.
+#[derive(Debug, Clone)]
+pub struct Chart {
+    /// The way series are presented.
+    pub kind: ChartKind,
+    /// The data.
+    pub series: Vec<Series>,
+    /// The horizontal axis.
+    pub x_axis: Axis,
+    /// The vertical axis.
+    pub y_axis: Axis,
+    /// Whether to draw a legend above the chart.
+    pub legend: bool,
+}
+
+impl Chart {
+    /// Returns a new chart with automatically scaled axes and a legend.
+    #[must_use]
+    pub fn new(kind: ChartKind, series: Vec<Series>) -> Self {
+        Self {
+            kind,
+            series,
+            x_axis: Axis::default(),
+            y_axis: Axis::default(),
+            legend: true,
+        }
+    }
+
+    /// Returns a new line chart.
+    #[must_use]
+    pub fn line(series: Vec<Series>) -> Self {
+        Self::new(ChartKind::Line, series)
+    }
+
+    /// Returns a new bar chart.
+    #[must_use]
+    pub fn bar(series: Vec<Series>) -> Self {
+        Self::new(ChartKind::Bar, series)
+    }
+
+    /// Returns a new scatter chart.
+    #[must_use]
+    pub fn scatter(series: Vec<Series>) -> Self {
+        Self::new(ChartKind::Scatter, series)
+    }
+
+    /// Returns a new area chart.
+    #[must_use]
+    pub fn area(series: Vec<Series>) -> Self {
+        Self::new(ChartKind::Area, series)
+    }
+
+    /// Sets the horizontal axis and returns self.
+    #[must_use]
+    pub fn with_x_axis(mut self, axis: Axis) -> Self {
+        self.x_axis = axis;
+        self
+    }
+
+    /// Sets the vertical axis and returns self.
+    #[must_use]
+    pub fn with_y_axis(mut self, axis: Axis) -> Self {
+        self.y_axis = axis;
+        self
+    }
+
+    /// Sets whether the legend is drawn and returns self.
+    #[must_use]
+    pub fn with_legend(mut self, legend: bool) -> Self {
+        self.legend = legend;
+        self
+    }
+
+    fn series_color(&self, index: usize) -> Color {
+        self.series[index]
+            .color
+            .unwrap_or(PALETTE[index % PALETTE.len()])
+    }
+
+    /// Returns the range of the data on each axis.
+    fn data_ranges(&self) -> ((f32, f32), (f32, f32)) {
+        let mut points = self
+            .series
+            .iter()
+            .flat_map(|series| series.points.iter().copied())
+            .filter(|(x, y)| x.is_finite() && y.is_finite());
+        let first = points.next().unwrap_or((0., 0.));
+        let (mut x, mut y) = points.fold(
+            ((first.0, first.0), (first.1, first.1)),
+            |((x_min, x_max), (y_min, y_max)), (x, y)| {
+                ((x_min.min(x), x_max.max(x)), (y_min.min(y), y_max.max(y)))
+            },
+        );
+        // Bars and areas are measured from zero.
+        if matches!(self.kind, ChartKind::Bar | ChartKind::Area) {
+            y = (y.0.min(0.), y.1.max(0.));
+        }
+        // Leave room for the bars on either side of the first and last values.
+        if self.kind == ChartKind::Bar {
+            let spacing = self.bar_spacing();
+            x = (x.0 - spacing / 2., x.1 + spacing / 2.);
+        }
+        (x, y)
+    }
+
+    /// Returns the smallest distance between two distinct x values.
+    fn bar_spacing(&self) -> f32 {
+        let mut xs = self
+            .series
+            .iter()
+            .flat_map(|series| series.points.iter().map(|(x, _)| *x))
+            .collect::<Vec<_>>();
+        xs.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        xs.windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .filter(|spacing| *spacing > f32::EPSILON)
+            .reduce(f32::min)
+            .unwrap_or(1.)
+    }
+
+    fn render_legend(
+        &self,
+        renderer: &CanvasRenderer,
+        bounds: &ExtentsRect<f32, Scaled>,
+        text: &TextOptions,
+        line_height: f32,
+    ) {
+        let mut x = bounds.origin.x;
+        for (index, series) in self.series.iter().enumerate() {
+            renderer.fill_rect(
+                &Rect::from(SizedRect::new(
+                    Point::new(x, bounds.origin.y),
+                    Size::new(line_height, line_height),
+                )),
+                self.series_color(index),
+            );
+            x += line_height + SPACING / 2.;
+            renderer.render_text(
+                &series.name,
+                Point::<f32, Scaled>::new(x, bounds.origin.y + line_height * 0.8),
+                text,
+            );
+            x += renderer.measure_text(&series.name, text).width.get() + SPACING * 2.;
+        }
+    }
+
+    fn render_series(&self, renderer: &CanvasRenderer, plot: &Plot) {
+        let bar_width = self.bar_spacing() * 0.8 / self.series.len().max(1) as f32;
+        for (index, series) in self.series.iter().enumerate() {
+            let color = self.series_color(index);
+            let stroke = StrokeOptions {
+                color,
+                line_width: Figure::new(2.),
+                ..StrokeOptions::default()
+            };
+            let points = series
+                .points
+                .iter()
+                .map(|&(x, y)| plot.map(x, y))
+                .collect::<Vec<_>>();
+            match self.kind {
+                ChartKind::Line => {
+                    for pair in points.windows(2) {
+                        renderer.stroke_line(pair[0], pair[1], &stroke);
+                    }
+                }
+                ChartKind::Scatter => {
+                    for point in points {
+                        renderer.fill_rect(
+                            &Rect::from(ExtentsRect::new(
+                                Point::new(point.x - 3., point.y - 3.),
+                                Point::new(point.x + 3., point.y + 3.),
+                            )),
+                            color,
+                        );
+                    }
+                }
+                ChartKind::Area => {
+                    fill_area(renderer, &points, plot.map(0., 0.).y, color);
+                    for pair in points.windows(2) {
+                        renderer.stroke_line(pair[0], pair[1], &stroke);
+                    }
+                }
+                ChartKind::Bar => {
+                    let offset = bar_width * (index as f32 - (self.series.len() as f32 - 1.) / 2.);
+                    for &(x, y) in &series.points {
+                        let top_left = plot.map(x + offset - bar_width / 2., y.max(0.));
+                        let bottom_right = plot.map(x + offset + bar_width / 2., y.min(0.));
+                        renderer.fill_rect(
+                            &Rect::from(ExtentsRect::new(top_left, bottom_right)),
+                            color,
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Fills the area between the line through `points` and the horizontal line
+/// at `zero`.
+fn fill_area(renderer: &CanvasRenderer, points: &[Point<f32, Scaled>], zero: f32, color: Color) {
+    let (first, last) = match (points.first(), points.last()) {
+        (Some(first), Some(last)) if points.len() > 1 => (first, last),
+        _ => return,
+    };
+    // Where the line crosses zero, the outline crosses itself, and the
+    // even-odd rule still fills both sides.
+    let outline = Path::from_points(
+        std::iter::once(Point::new(first.x, zero))
+            .chain(points.iter().copied())
+            .chain(std::iter::once(Point::new(last.x, zero))),
+    )
+    .close();
+    if let Some(bounds) = outline.bounds() {
+        renderer.clip_to_path(&outline).fill_rect(&bounds, color);
+    }
+}
+
+/// Maps data coordinates into the plotting area.
+struct Plot {
+    area: ExtentsRect<f32, Scaled>,
+    x: (f32, f32),
+    y: (f32, f32),
+}
+
+impl Plot {
+    fn map(&self, x: f32, y: f32) -> Point<f32, Scaled> {
+        let width = self.area.extent.x - self.area.origin.x;
+        let height = self.area.extent.y - self.area.origin.y;
+        Point::new(
+            self.area.origin.x + (x - self.x.0) / (self.x.1 - self.x.0) * width,
+            self.area.extent.y - (y - self.y.0) / (self.y.1 - self.y.0) * height,
+        )
+    }
+}
+
+/// Returns evenly spaced, round values covering `range`, and the range
+/// expanded to the first and last tick.
+///
+/// A reversed range is treated as if its ends were swapped, an empty range is
+/// widened by 1 on either side, and a range with an end that isn't finite is
+/// replaced by `0..1`.
+#[must_use]
+pub fn ticks(range: (f32, f32), count: usize) -> (Vec<f32>, (f32, f32)) {
+    let (min, max) = if !range.0.is_finite() || !range.1.is_finite() {
+        (0., 1.)
+    } else if (range.1 - range.0).abs() < f32::EPSILON {
+        (range.0 - 1., range.1 + 1.)
+    } else {
+        (range.0.min(range.1), range.0.max(range.1))
+    };
+    let step = nice_step((max - min) / count.max(2).saturating_sub(1) as f32);
+    let first = (min / step).floor() * step;
+    let last = (max / step).ceil() * step;
+    // Nice steps divide the range into at most a few times `count` steps. The
+    // limit only matters when the range is too wide to measure in an `f32`.
+    let steps = (((last - first) / step).round() as usize).min(count.max(2) * 10);
+    let ticks = (0..=steps)
+        .map(|index| first + step * index as f32)
+        .collect();
+    (ticks, (first, last))
+}
+
+/// Rounds `step` to 1, 2 or 5 times a power of ten. Steps that aren't
+/// positive and finite become 1.
+fn nice_step(step: f32) -> f32 {
+    if !step.is_finite() || step <= 0. {
+        return 1.;
+    }
+    let magnitude = 10_f32.powf(step.log10().floor());
+    let fraction = step / magnitude;
+    let nice = if fraction <= 1. {
+        1.
+    } else if fraction <= 2. {
+        2.
+    } else if fraction <= 5. {
+        5.
+    } else {
+        10.
+    };
+    nice * magnitude
+}
+
+fn format_tick(value: f32, step: f32) -> String {
+    let decimals = (-step.log10().floor()).max(0.) as usize;
+    format!("{:.*}", decimals, value)
+}
+
+impl Renderable for Chart {
+    fn receive(&mut self, message: Box<dyn Any + Send + Sync>) {
+        if let Ok(command) = message.downcast::<ChartCommand>() {
+            match *command {
+                ChartCommand::SetSeries(series) => self.series = series,
+                ChartCommand::Push { series, point } => {
+                    if let Some(series) = self.series.get_mut(series) {
+                        series.points.push(point);
+                    }
+                }
+            }
+        }
+    }
+
+    fn render(&mut self, renderer: CanvasRenderer, content_area: &ContentArea) {
+        let foreground = match renderer.theme() {
+            SystemTheme::Dark => Color::WHITE,
+            SystemTheme::Light => Color::BLACK,
+        };
+        let grid = match renderer.theme() {
+            SystemTheme::Dark => Color::DIMGRAY,
+            SystemTheme::Light => Color::LIGHTGRAY,
+        };
+        let text = TextOptions {
+            color: foreground,
+            ..TextOptions::default()
+        };
+        let axis_stroke = StrokeOptions {
+            color: foreground,
+            line_width: Figure::new(1.),
+            ..StrokeOptions::default()
+        };
+        let grid_stroke = StrokeOptions {
+            color: grid,
+            ..axis_stroke.clone()
+        };
+
+        let metrics = renderer.measure_text("0", &text);
+        let line_height = metrics.ascent.get() + metrics.descent.get().abs();
+
+        let (data_x, data_y) = self.data_ranges();
+        let (x_ticks, x_range) = ticks(self.x_axis.range.unwrap_or(data_x), self.x_axis.ticks);
+        let (y_ticks, y_range) = ticks(self.y_axis.range.unwrap_or(data_y), self.y_axis.ticks);
+        let x_step = x_ticks.get(1).map_or(1., |second| second - x_ticks[0]);
+        let y_step = y_ticks.get(1).map_or(1., |second| second - y_ticks[0]);
+        let y_labels = y_ticks
+            .iter()
+            .map(|value| format_tick(*value, y_step))
+            .collect::<Vec<_>>();
+        let y_label_width = y_labels
+            .iter()
+            .map(|label| renderer.measure_text(label, &text).width.get())
+            .fold(0., f32::max);
+
+        let mut bounds = content_area.bounds().as_extents();
+        bounds.origin.x += SPACING;
+        bounds.origin.y += SPACING;
+        bounds.extent.x -= SPACING;
+        bounds.extent.y -= SPACING;
+        if self.legend && !self.series.is_empty() {
+            self.render_legend(&renderer, &bounds, &text, line_height);
+            bounds.origin.y += line_height + SPACING * 2.;
+        }
+
+        let plot = Plot {
+            area: ExtentsRect::new(
+                Point::new(bounds.origin.x + y_label_width + SPACING, bounds.origin.y),
+                Point::new(bounds.extent.x, bounds.extent.y - line_height - SPACING),
+            ),
+            x: x_range,
+            y: y_range,
+        };
+        if plot.area.extent.x <= plot.area.origin.x || plot.area.extent.y <= plot.area.origin.y {
+            return;
+        }
+
+        // Grid lines and tick labels.
+        for (value, label) in y_ticks.iter().zip(&y_labels) {
+            let y = plot.map(x_range.0, *value).y;
+            renderer.stroke_line(
+                Point::<f32, Scaled>::new(plot.area.origin.x, y),
+                Point::new(plot.area.extent.x, y),
+                &grid_stroke,
+            );
+            let width = renderer.measure_text(label, &text).width.get();
+            renderer.render_text(
+                label,
+                Point::<f32, Scaled>::new(
+                    plot.area.origin.x - SPACING - width,
+                    y + metrics.ascent.get() / 2.,
+                ),
+                &text,
+            );
+        }
+        for value in &x_ticks {
+            let x = plot.map(*value, y_range.0).x;
+            renderer.stroke_line(
+                Point::<f32, Scaled>::new(x, plot.area.origin.y),
+                Point::new(x, plot.area.extent.y),
+                &grid_stroke,
+            );
+            let label = format_tick(*value, x_step);
+            let width = renderer.measure_text(&label, &text).width.get();
+            renderer.render_text(
+                &label,
+                Point::<f32, Scaled>::new(
+                    x - width / 2.,
+                    plot.area.extent.y + SPACING + metrics.ascent.get(),
+                ),
+                &text,
+            );
+        }
+
+        self.render_series(&renderer, &plot);
+
+        // Axes
+        renderer.stroke_line(
+            Point::<f32, Scaled>::new(plot.area.origin.x, plot.area.origin.y),
+            Point::new(plot.area.origin.x, plot.area.extent.y),
+            &axis_stroke,
+        );
+        renderer.stroke_line(
+            Point::<f32, Scaled>::new(plot.area.origin.x, plot.area.extent.y),
+            Point::new(plot.area.extent.x, plot.area.extent.y),
+            &axis_stroke,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() <= expected.abs() * 1e-5,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn steps_are_round() {
+        assert_close(nice_step(0.7), 1.);
+        assert_close(nice_step(1.5), 2.);
+        assert_close(nice_step(3.), 5.);
+        assert_close(nice_step(7.), 10.);
+        assert_close(nice_step(0.03), 0.05);
+        assert_close(nice_step(250.), 500.);
+    }
+
+    #[test]
+    fn degenerate_steps_are_one() {
+        for step in [0., -1., f32::NAN, f32::INFINITY] {
+            assert_close(nice_step(step), 1.);
+        }
+    }
+
+    #[test]
+    fn ticks_cover_the_range() {
+        let (ticks, range) = ticks((0.3, 9.2), 6);
+        assert_eq!(ticks, vec![0., 2., 4., 6., 8., 10.]);
+        assert_eq!(range, (0., 10.));
+    }
+
+    #[test]
+    fn reversed_ranges_are_swapped() {
+        assert_eq!(ticks((10., 0.), 3), ticks((0., 10.), 3));
+    }
+
+    #[test]
+    fn empty_ranges_are_widened() {
+        let (ticks, range) = ticks((4., 4.), 3);
+        assert_eq!(range, (3., 5.));
+        assert_eq!(ticks, vec![3., 4., 5.]);
+    }
+
+    #[test]
+    fn ranges_that_are_not_finite_are_replaced() {
+        for range in [(f32::NAN, 1.), (0., f32::INFINITY), (f32::NAN, f32::NAN)] {
+            let (ticks, range) = ticks(range, 3);
+            assert!(ticks.iter().all(|tick| tick.is_finite()));
+            assert_eq!(range, (0., 1.));
+        }
+    }
+
+    #[test]
+    fn huge_ranges_have_few_ticks() {
+        let (ticks, _) = ticks((-f32::MAX, f32::MAX), 5);
+        assert!(ticks.len() <= 51);
+    }
+}
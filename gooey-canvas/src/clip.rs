@@ -0,0 +1,58 @@
+use gooey::core::{
+    figures::{Point, Rect, Rectlike, Size, SizedRect},
+    Scaled,
+};
+
+use crate::{Path, Transform};
+
+/// A non-rectangular clipping region, in canvas coordinates.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ClipShape {
+    RoundedRect {
+        rect: Rect<f32, Scaled>,
+        radius: f32,
+    },
+    /// A closed polygon, filled using the even-odd rule.
+    Polygon(Vec<Point<f32, Scaled>>),
+}
+
+impl ClipShape {
+    pub(crate) fn rounded_rect(rect: Rect<f32, Scaled>, radius: f32) -> Self {
+        let sized = rect.as_sized();
+        let radius = radius
+            .max(0.)
+            .min(sized.size.width.abs() / 2.)
+            .min(sized.size.height.abs() / 2.);
+        Self::RoundedRect { rect, radius }
+    }
+
+    pub(crate) fn path(path: &Path) -> Self {
+        Self::Polygon(path.points().to_vec())
+    }
+
+    /// Returns this shape after applying `transform`.
+    pub(crate) fn transformed(&self, transform: &Transform) -> Self {
+        match self {
+            Self::RoundedRect { rect, radius } => Self::RoundedRect {
+                rect: transform.transform_rect(*rect),
+                radius: radius * transform.scale,
+            },
+            Self::Polygon(points) => Self::Polygon(
+                points
+                    .iter()
+                    .map(|point| transform.transform_point(*point))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Returns the smallest rectangle containing this shape.
+    pub(crate) fn bounds(&self) -> Rect<f32, Scaled> {
+        match self {
+            Self::RoundedRect { rect, .. } => *rect,
+            Self::Polygon(points) => Path::from_points(points.iter().copied())
+                .bounds()
+                .unwrap_or_else(|| Rect::from(SizedRect::new(Point::default(), Size::default()))),
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use gooey::core::{
+    figures::{ExtentsRect, Point, Rect, Rectlike},
+    Scaled,
+};
+
+/// A mouse cursor shown over a [`Canvas`](crate::Canvas).
+///
+/// Only the browser shows cursors. Kludgine doesn't support them: the
+/// rasterizer doesn't let widgets change the window's cursor, so the Kludgine
+/// frontend ignores [`Command::SetCursor`](crate::Command::SetCursor) and the
+/// cursors set while drawing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CursorIcon {
+    /// The platform's default arrow.
+    Default,
+    /// A pointing hand, used for links and buttons.
+    Pointer,
+    /// A text insertion bar.
+    Text,
+    /// A precise crosshair.
+    Crosshair,
+    /// Arrows in every direction, for moving an object.
+    Move,
+    /// An open hand, for something that can be grabbed.
+    Grab,
+    /// A closed hand, for something being dragged.
+    Grabbing,
+    /// Shows that the action isn't allowed.
+    NotAllowed,
+    /// Shows that the application is busy.
+    Wait,
+    /// A horizontal double arrow.
+    ResizeHorizontal,
+    /// A vertical double arrow.
+    ResizeVertical,
+    /// A double arrow from the top-left to the bottom-right.
+    ResizeDiagonal,
+    /// A double arrow from the top-right to the bottom-left.
+    ResizeAntiDiagonal,
+    /// No cursor is shown.
+    Hidden,
+}
+
+impl Default for CursorIcon {
+    fn default() -> Self {
+        Self::Default
+    }
+}
+
+impl CursorIcon {
+    /// Returns the value of the CSS `cursor` property for this icon.
+    #[must_use]
+    pub const fn css_name(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Pointer => "pointer",
+            Self::Text => "text",
+            Self::Crosshair => "crosshair",
+            Self::Move => "move",
+            Self::Grab => "grab",
+            Self::Grabbing => "grabbing",
+            Self::NotAllowed => "not-allowed",
+            Self::Wait => "wait",
+            Self::ResizeHorizontal => "ew-resize",
+            Self::ResizeVertical => "ns-resize",
+            Self::ResizeDiagonal => "nwse-resize",
+            Self::ResizeAntiDiagonal => "nesw-resize",
+            Self::Hidden => "none",
+        }
+    }
+}
+
+/// The cursors requested while drawing a frame, in the backend's
+/// coordinates.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Cursors {
+    pub frame: Option<CursorIcon>,
+    pub regions: Vec<(Rect<f32, Scaled>, CursorIcon)>,
+}
+
+impl Cursors {
+    /// Returns the cursor of the last region drawn that contains `location`,
+    /// or the cursor for the whole frame if none do.
+    pub(crate) fn cursor_at(&self, location: Point<f32, Scaled>) -> Option<CursorIcon> {
+        self.regions
+            .iter()
+            .rev()
+            .find(|(region, _)| {
+                let region = region.as_sized();
+                let (left, right) = ordered(region.origin.x, region.size.width);
+                let (top, bottom) = ordered(region.origin.y, region.size.height);
+                location.x >= left && location.x < right && location.y >= top && location.y < bottom
+            })
+            .map(|(_, cursor)| *cursor)
+            .or(self.frame)
+    }
+
+    /// Returns these cursors, requested while drawing only `dirty_region`,
+    /// combined with the parts of `previous` that are still on screen: its
+    /// regions outside of `dirty_region`, and its frame cursor if this frame
+    /// didn't set one.
+    pub(crate) fn drawn_over(self, previous: Self, dirty_region: Rect<f32, Scaled>) -> Self {
+        let mut regions = Vec::with_capacity(previous.regions.len() + self.regions.len());
+        for (region, cursor) in previous.regions {
+            regions.extend(
+                subtract(region, dirty_region)
+                    .into_iter()
+                    .map(|region| (region, cursor)),
+            );
+        }
+        regions.extend(self.regions);
+        Self {
+            frame: self.frame.or(previous.frame),
+            regions,
+        }
+    }
+}
+
+/// Returns the edges of `rect` as `(left, top, right, bottom)`.
+fn edges(rect: Rect<f32, Scaled>) -> (f32, f32, f32, f32) {
+    let rect = rect.as_sized();
+    let (left, right) = ordered(rect.origin.x, rect.size.width);
+    let (top, bottom) = ordered(rect.origin.y, rect.size.height);
+    (left, top, right, bottom)
+}
+
+/// Returns up to four rectangles covering the parts of `rect` outside of
+/// `removed`.
+fn subtract(rect: Rect<f32, Scaled>, removed: Rect<f32, Scaled>) -> Vec<Rect<f32, Scaled>> {
+    let (left, top, right, bottom) = edges(rect);
+    let (removed_left, removed_top, removed_right, removed_bottom) = edges(removed);
+    if removed_left >= right
+        || removed_right <= left
+        || removed_top >= bottom
+        || removed_bottom <= top
+    {
+        return vec![rect];
+    }
+
+    let middle_top = top.max(removed_top);
+    let middle_bottom = bottom.min(removed_bottom);
+    [
+        (left, top, right, middle_top),
+        (left, middle_top, removed_left.max(left), middle_bottom),
+        (removed_right.min(right), middle_top, right, middle_bottom),
+        (left, middle_bottom, right, bottom),
+    ]
+    .iter()
+    .filter(|(left, top, right, bottom)| right > left && bottom > top)
+    .map(|&(left, top, right, bottom)| {
+        Rect::from(ExtentsRect::new(
+            Point::new(left, top),
+            Point::new(right, bottom),
+        ))
+    })
+    .collect()
+}
+
+/// Returns the start and end of a span that may have a negative length.
+fn ordered(origin: f32, length: f32) -> (f32, f32) {
+    if length < 0. {
+        (origin + length, origin)
+    } else {
+        (origin, origin + length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gooey::core::figures::{Size, SizedRect};
+
+    use super::*;
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect<f32, Scaled> {
+        Rect::from(SizedRect::new(Point::new(x, y), Size::new(width, height)))
+    }
+
+    #[test]
+    fn subtracting_leaves_the_uncovered_parts() {
+        let parts = subtract(rect(0., 0., 30., 30.), rect(10., 10., 10., 10.))
+            .into_iter()
+            .map(edges)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            parts,
+            vec![
+                (0., 0., 30., 10.),
+                (0., 10., 10., 20.),
+                (20., 10., 30., 20.),
+                (0., 20., 30., 30.),
+            ]
+        );
+        assert!(subtract(rect(10., 10., 5., 5.), rect(0., 0., 30., 30.)).is_empty());
+        assert_eq!(
+            edges(subtract(rect(0., 0., 5., 5.), rect(10., 10., 5., 5.))[0]),
+            (0., 0., 5., 5.)
+        );
+    }
+
+    #[test]
+    fn partial_frames_keep_the_cursors_they_didnt_redraw() {
+        let previous = Cursors {
+            frame: Some(CursorIcon::Crosshair),
+            regions: vec![
+                (rect(0., 0., 10., 10.), CursorIcon::Pointer),
+                (rect(50., 50., 10., 10.), CursorIcon::Text),
+            ],
+        };
+        // Only the second region is redrawn, and it's no longer there.
+        let cursors = Cursors::default().drawn_over(previous.clone(), rect(40., 40., 30., 30.));
+        assert_eq!(
+            cursors.cursor_at(Point::new(5., 5.)),
+            Some(CursorIcon::Pointer)
+        );
+        assert_eq!(
+            cursors.cursor_at(Point::new(55., 55.)),
+            Some(CursorIcon::Crosshair)
+        );
+
+        // Regions and cursors set by the new frame take precedence.
+        let cursors = Cursors {
+            frame: Some(CursorIcon::Wait),
+            regions: vec![(rect(0., 0., 10., 10.), CursorIcon::Move)],
+        }
+        .drawn_over(previous, rect(5., 5., 1., 1.));
+        assert_eq!(
+            cursors.cursor_at(Point::new(1., 1.)),
+            Some(CursorIcon::Move)
+        );
+        assert_eq!(
+            cursors.cursor_at(Point::new(30., 30.)),
+            Some(CursorIcon::Wait)
+        );
+    }
+}
@@ -0,0 +1,103 @@
+use std::{any::Any, fmt::Debug, sync::Arc};
+
+use gooey::core::{figures::Point, Scaled};
+
+/// A drag-and-drop interaction over a [`Canvas`](crate::Canvas).
+///
+/// The browser reports drags from outside of the page. Kludgine doesn't
+/// support dragging files onto the window: it only reports drags delivered
+/// with [`Command::Drag`](crate::Command::Drag).
+#[derive(Debug, Clone)]
+pub struct DragEvent {
+    /// What happened.
+    pub kind: DragEventKind,
+    /// The location of the pointer, relative to the canvas.
+    pub location: Point<f32, Scaled>,
+}
+
+/// The kind of a [`DragEvent`].
+///
+/// Browsers only reveal what is being dragged once it is dropped, so the
+/// payload is only available with [`Drop`](Self::Drop).
+#[derive(Debug, Clone)]
+pub enum DragEventKind {
+    /// A drag entered the canvas.
+    Enter,
+    /// A drag moved over the canvas.
+    Over,
+    /// A drag left the canvas without dropping.
+    Leave,
+    /// The payload was dropped on the canvas.
+    Drop(DragPayload),
+}
+
+/// The contents of a drag.
+#[derive(Clone)]
+pub enum DragPayload {
+    /// Files, such as images dragged from the desktop.
+    Files(Vec<DroppedFile>),
+    /// Plain text.
+    Text(String),
+    /// Data from elsewhere in the application, delivered with
+    /// [`Command::Drag`](crate::Command::Drag).
+    Data(Arc<dyn Any + Send + Sync>),
+}
+
+impl Debug for DragPayload {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Files(files) => f.debug_tuple("Files").field(files).finish(),
+            Self::Text(text) => f.debug_tuple("Text").field(text).finish(),
+            Self::Data(_) => f.write_str("Data(..)"),
+        }
+    }
+}
+
+/// A file dropped on a [`Canvas`](crate::Canvas).
+#[derive(Clone)]
+pub struct DroppedFile {
+    /// The name of the file, without its directory.
+    pub name: String,
+    /// The contents of the file.
+    pub bytes: Vec<u8>,
+}
+
+impl DroppedFile {
+    /// Reads the file at `path`, such as a path reported by winit's
+    /// `DroppedFile` event.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> std::io::Result<Self> {
+        let path = path.as_ref();
+        Ok(Self {
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            bytes: std::fs::read(path)?,
+        })
+    }
+}
+
+impl Debug for DroppedFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DroppedFile")
+            .field("name", &self.name)
+            .field("len", &self.bytes.len())
+            .finish()
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_are_read_from_paths() {
+        let path = std::env::temp_dir().join("gooey-canvas-dropped-file.txt");
+        std::fs::write(&path, b"floor plan").unwrap();
+        let file = DroppedFile::from_path(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(file.name, "gooey-canvas-dropped-file.txt");
+        assert_eq!(file.bytes, b"floor plan");
+    }
+}
@@ -0,0 +1,285 @@
+use gooey::core::{
+    figures::{Point, Vector},
+    Scaled,
+};
+
+use crate::{Modifiers, PointerButton, PointerEvent, PointerEventKind};
+
+/// Turns pointer events into drags, for things like sliders, handles and
+/// node editors. Feed it every event given to
+/// [`Renderable::pointer()`](crate::Renderable::pointer).
+///
+/// Canvases capture the pointer while a button is held, so a drag keeps
+/// receiving moves and always ends, even if the pointer leaves the canvas.
+#[derive(Debug, Clone, Default)]
+pub struct DragTracker {
+    /// How far the pointer must move, in canvas coordinates, before a press
+    /// becomes a drag.
+    threshold: f32,
+    drags: Vec<ActiveDrag>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ActiveDrag {
+    pointer_id: u32,
+    button: PointerButton,
+    start: Point<f32, Scaled>,
+    last: Point<f32, Scaled>,
+    started: bool,
+}
+
+/// A step of a drag reported by a [`DragTracker`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointerDrag {
+    /// Which step of the drag this is.
+    pub phase: DragPhase,
+    /// The button that was pressed to start the drag.
+    pub button: PointerButton,
+    /// The modifier keys held during this step. Like
+    /// [`PointerEvent::modifiers`], these are never held on Kludgine.
+    pub modifiers: Modifiers,
+    /// Where the button was pressed.
+    pub start: Point<f32, Scaled>,
+    /// The current location of the pointer.
+    pub location: Point<f32, Scaled>,
+    /// How far the pointer moved since the previous step.
+    pub delta: Vector<f32, Scaled>,
+}
+
+impl PointerDrag {
+    /// Returns how far the pointer has moved since the drag started.
+    #[must_use]
+    pub fn total(&self) -> Vector<f32, Scaled> {
+        Vector::new(
+            self.location.x - self.start.x,
+            self.location.y - self.start.y,
+        )
+    }
+}
+
+/// The step of a [`PointerDrag`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DragPhase {
+    /// The pointer moved past the threshold with the button held.
+    Started,
+    /// The pointer moved during the drag.
+    Moved,
+    /// The button was released, ending the drag.
+    Ended,
+    /// The frontend took the pointer over, ending the drag without the button
+    /// being released. The drag's changes should usually be reverted.
+    Cancelled,
+}
+
+impl DragTracker {
+    /// Returns a tracker that starts a drag as soon as a pressed pointer
+    /// moves.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how far the pointer must move before a press becomes a drag, and
+    /// returns self. Presses that never move that far remain clicks.
+    #[must_use]
+    pub fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold.max(0.);
+        self
+    }
+
+    /// Returns true if any pointer is dragging.
+    #[must_use]
+    pub fn is_dragging(&self) -> bool {
+        self.drags.iter().any(|drag| drag.started)
+    }
+
+    /// Updates the tracker with `event`, returning the step of the drag it
+    /// causes, if any.
+    pub fn pointer(&mut self, event: &PointerEvent) -> Option<PointerDrag> {
+        match event.kind {
+            PointerEventKind::Down(button) => {
+                self.drags
+                    .retain(|drag| drag.pointer_id != event.pointer_id);
+                self.drags.push(ActiveDrag {
+                    pointer_id: event.pointer_id,
+                    button,
+                    start: event.location,
+                    last: event.location,
+                    started: false,
+                });
+                None
+            }
+            PointerEventKind::Move => {
+                let threshold = self.threshold;
+                let drag = self
+                    .drags
+                    .iter_mut()
+                    .find(|drag| drag.pointer_id == event.pointer_id)?;
+                let phase = if drag.started {
+                    DragPhase::Moved
+                } else {
+                    let distance =
+                        (event.location.x - drag.start.x).hypot(event.location.y - drag.start.y);
+                    if distance <= threshold {
+                        return None;
+                    }
+                    drag.started = true;
+                    DragPhase::Started
+                };
+                Some(step(drag, phase, event))
+            }
+            PointerEventKind::Up(_) => {
+                let index = self
+                    .drags
+                    .iter()
+                    .position(|drag| drag.pointer_id == event.pointer_id)?;
+                let mut drag = self.drags.remove(index);
+                drag.started
+                    .then(|| step(&mut drag, DragPhase::Ended, event))
+            }
+            PointerEventKind::Cancel => {
+                let index = self
+                    .drags
+                    .iter()
+                    .position(|drag| drag.pointer_id == event.pointer_id)?;
+                let mut drag = self.drags.remove(index);
+                drag.started
+                    .then(|| step(&mut drag, DragPhase::Cancelled, event))
+            }
+            PointerEventKind::Wheel(_) => None,
+        }
+    }
+}
+
+fn step(drag: &mut ActiveDrag, phase: DragPhase, event: &PointerEvent) -> PointerDrag {
+    let delta = Vector::new(
+        event.location.x - drag.last.x,
+        event.location.y - drag.last.y,
+    );
+    drag.last = event.location;
+    PointerDrag {
+        phase,
+        button: drag.button,
+        modifiers: event.modifiers,
+        start: drag.start,
+        location: event.location,
+        delta,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PointerDevice;
+
+    fn event(pointer_id: u32, kind: PointerEventKind, x: f32, y: f32) -> PointerEvent {
+        PointerEvent {
+            kind,
+            location: Point::new(x, y),
+            pointer_id,
+            device: PointerDevice::Touch,
+            modifiers: Modifiers {
+                shift: true,
+                ..Modifiers::default()
+            },
+        }
+    }
+
+    fn down(pointer_id: u32, x: f32, y: f32) -> PointerEvent {
+        event(
+            pointer_id,
+            PointerEventKind::Down(PointerButton::Primary),
+            x,
+            y,
+        )
+    }
+
+    fn moved(pointer_id: u32, x: f32, y: f32) -> PointerEvent {
+        event(pointer_id, PointerEventKind::Move, x, y)
+    }
+
+    fn up(pointer_id: u32, x: f32, y: f32) -> PointerEvent {
+        event(
+            pointer_id,
+            PointerEventKind::Up(PointerButton::Primary),
+            x,
+            y,
+        )
+    }
+
+    #[test]
+    fn drags_start_move_and_end() {
+        let mut tracker = DragTracker::new();
+        assert_eq!(tracker.pointer(&down(0, 10., 10.)), None);
+        assert!(!tracker.is_dragging());
+
+        let started = tracker.pointer(&moved(0, 15., 10.)).unwrap();
+        assert_eq!(started.phase, DragPhase::Started);
+        assert_eq!(started.button, PointerButton::Primary);
+        assert!(started.modifiers.shift);
+        assert_eq!(started.delta, Vector::new(5., 0.));
+        assert!(tracker.is_dragging());
+
+        let moved = tracker.pointer(&moved(0, 15., 30.)).unwrap();
+        assert_eq!(moved.phase, DragPhase::Moved);
+        assert_eq!(moved.delta, Vector::new(0., 20.));
+        assert_eq!(moved.total(), Vector::new(5., 20.));
+
+        let ended = tracker.pointer(&up(0, 20., 30.)).unwrap();
+        assert_eq!(ended.phase, DragPhase::Ended);
+        assert_eq!(ended.start, Point::new(10., 10.));
+        assert_eq!(ended.delta, Vector::new(5., 0.));
+        assert!(!tracker.is_dragging());
+        assert_eq!(tracker.pointer(&moved(0, 40., 40.)), None);
+    }
+
+    #[test]
+    fn presses_within_the_threshold_stay_clicks() {
+        let mut tracker = DragTracker::new().with_threshold(5.);
+        tracker.pointer(&down(0, 10., 10.));
+        assert_eq!(tracker.pointer(&moved(0, 13., 14.)), None);
+        assert_eq!(tracker.pointer(&up(0, 13., 14.)), None);
+
+        tracker.pointer(&down(0, 10., 10.));
+        let started = tracker.pointer(&moved(0, 16., 10.)).unwrap();
+        assert_eq!(started.phase, DragPhase::Started);
+        assert_eq!(started.delta, Vector::new(6., 0.));
+    }
+
+    #[test]
+    fn pointers_drag_independently() {
+        let mut tracker = DragTracker::new();
+        tracker.pointer(&down(0, 0., 0.));
+        tracker.pointer(&down(1, 100., 100.));
+        let first = tracker.pointer(&moved(0, 10., 0.)).unwrap();
+        let second = tracker.pointer(&moved(1, 100., 90.)).unwrap();
+        assert_eq!(first.total(), Vector::new(10., 0.));
+        assert_eq!(second.total(), Vector::new(0., -10.));
+
+        assert_eq!(
+            tracker.pointer(&up(0, 10., 0.)).map(|drag| drag.phase),
+            Some(DragPhase::Ended)
+        );
+        assert!(tracker.is_dragging());
+    }
+
+    #[test]
+    fn cancelled_pointers_cancel_their_drag() {
+        let mut tracker = DragTracker::new();
+        tracker.pointer(&down(0, 0., 0.));
+        tracker.pointer(&moved(0, 10., 0.));
+        let cancelled = tracker
+            .pointer(&event(0, PointerEventKind::Cancel, 10., 0.))
+            .unwrap();
+        assert_eq!(cancelled.phase, DragPhase::Cancelled);
+        assert!(!tracker.is_dragging());
+        assert_eq!(tracker.pointer(&up(0, 10., 0.)), None);
+
+        // Cancelling a press that never became a drag reports nothing.
+        tracker.pointer(&down(0, 0., 0.));
+        assert_eq!(
+            tracker.pointer(&event(0, PointerEventKind::Cancel, 0., 0.)),
+            None
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use gooey::core::{figures::Vector, styles::Color, Scaled};
+
+use crate::Transform;
+
+/// A shadow drawn beneath filled rectangles, text and images. Set with
+/// [`CanvasRenderer::with_shadow()`](crate::CanvasRenderer::with_shadow).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shadow {
+    /// How far the shadow is offset from the shape casting it.
+    pub offset: Vector<f32, Scaled>,
+    /// How far beyond the shape's edges the shadow fades out. A radius of 0
+    /// draws a shadow with hard edges.
+    pub blur_radius: f32,
+    /// The color of the shadow.
+    pub color: Color,
+}
+
+impl Shadow {
+    /// Returns a shadow offset by `offset` that fades out over `blur_radius`.
+    #[must_use]
+    pub const fn new(offset: Vector<f32, Scaled>, blur_radius: f32, color: Color) -> Self {
+        Self {
+            offset,
+            blur_radius,
+            color,
+        }
+    }
+}
+
+/// The effects applied when drawing, in canvas coordinates.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct Effects {
+    pub shadow: Option<Shadow>,
+    pub blur_radius: f32,
+}
+
+impl Effects {
+    /// Returns `shadow` in canvas coordinates, scaled by `transform`.
+    pub(crate) fn with_shadow(self, shadow: Option<Shadow>, transform: &Transform) -> Self {
+        Self {
+            shadow: shadow.map(|shadow| Shadow {
+                offset: Vector::new(
+                    shadow.offset.x * transform.scale,
+                    shadow.offset.y * transform.scale,
+                ),
+                blur_radius: shadow.blur_radius.max(0.) * transform.scale,
+                color: shadow.color,
+            }),
+            ..self
+        }
+    }
+
+    /// Returns a blur of `radius` in canvas coordinates, scaled by
+    /// `transform`.
+    pub(crate) fn with_blur(self, radius: f32, transform: &Transform) -> Self {
+        Self {
+            blur_radius: radius.max(0.) * transform.scale,
+            ..self
+        }
+    }
+}
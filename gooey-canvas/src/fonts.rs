@@ -0,0 +1,158 @@
+use std::{fmt::Debug, sync::Arc};
+
+/// A font registered with a [`Canvas`](crate::Canvas), either with
+/// [`Builder::with_font()`](crate::Builder::with_font) or
+/// [`Command::RegisterFont`](crate::Command::RegisterFont). Text uses the font
+/// when its [`TextOptions::font_family`](gooey::renderer::TextOptions) names
+/// the font's family.
+///
+/// The browser loads fonts with the `FontFace` API, adding each family to the
+/// page once, and redraws the canvas once the font is ready.
+///
+/// Kludgine doesn't support registered fonts: the rasterizer doesn't let
+/// widgets add fonts to Kludgine's font system, so the Kludgine frontend
+/// ignores them and keeps drawing text with its default font.
+#[derive(Clone)]
+pub struct CanvasFont {
+    family: String,
+    source: FontSource,
+}
+
+#[derive(Clone)]
+pub(crate) enum FontSource {
+    Bytes(Arc<Vec<u8>>),
+    Url(String),
+}
+
+impl CanvasFont {
+    /// Returns a font for `family` from the contents of a TrueType, OpenType,
+    /// WOFF or WOFF2 file.
+    pub fn from_bytes(family: impl Into<String>, bytes: impl Into<Vec<u8>>) -> Self {
+        Self {
+            family: family.into(),
+            source: FontSource::Bytes(Arc::new(bytes.into())),
+        }
+    }
+
+    /// Returns a font for `family` from the file at `path`, such as a font
+    /// bundled with the app's assets.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_file(
+        family: impl Into<String>,
+        path: impl AsRef<std::path::Path>,
+    ) -> std::io::Result<Self> {
+        Ok(Self::from_bytes(family, std::fs::read(path)?))
+    }
+
+    /// Returns a font for `family` that the browser downloads from `url`,
+    /// such as the URL the app's assets are served from. The browser resolves
+    /// relative URLs against the page. Only the browser loads fonts from
+    /// URLs.
+    pub fn from_url(family: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            family: family.into(),
+            source: FontSource::Url(url.into()),
+        }
+    }
+
+    /// Returns the family that text refers to this font by.
+    #[must_use]
+    pub fn family(&self) -> &str {
+        &self.family
+    }
+
+    /// Returns the contents of the font file, or `None` if the font is loaded
+    /// from a URL.
+    #[must_use]
+    pub fn bytes(&self) -> Option<&[u8]> {
+        match &self.source {
+            FontSource::Bytes(bytes) => Some(bytes.as_slice()),
+            FontSource::Url(_) => None,
+        }
+    }
+
+    /// Returns the URL the font is loaded from, or `None` if it was created
+    /// from the contents of its file.
+    #[must_use]
+    pub fn url(&self) -> Option<&str> {
+        match &self.source {
+            FontSource::Bytes(_) => None,
+            FontSource::Url(url) => Some(url.as_str()),
+        }
+    }
+
+    /// Returns the format of the font file, or `None` if it isn't recognized.
+    /// The format of fonts loaded from URLs is guessed from their extension.
+    #[must_use]
+    pub fn format(&self) -> Option<FontFormat> {
+        match &self.source {
+            FontSource::Bytes(bytes) => match bytes.get(..4)? {
+                [0, 1, 0, 0] | b"true" => Some(FontFormat::TrueType),
+                b"OTTO" => Some(FontFormat::OpenType),
+                b"wOFF" => Some(FontFormat::Woff),
+                b"wOF2" => Some(FontFormat::Woff2),
+                _ => None,
+            },
+            FontSource::Url(url) => {
+                let path = url.split(|c| c == '?' || c == '#').next()?;
+                let extension = &path[path.rfind('.')? + 1..];
+                match extension.to_ascii_lowercase().as_str() {
+                    "ttf" => Some(FontFormat::TrueType),
+                    "otf" => Some(FontFormat::OpenType),
+                    "woff" => Some(FontFormat::Woff),
+                    "woff2" => Some(FontFormat::Woff2),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    pub(crate) const fn source(&self) -> &FontSource {
+        &self.source
+    }
+}
+
+impl Debug for CanvasFont {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("CanvasFont");
+        f.field("family", &self.family);
+        match &self.source {
+            FontSource::Bytes(bytes) => f.field("len", &bytes.len()),
+            FontSource::Url(url) => f.field("url", url),
+        };
+        f.finish()
+    }
+}
+
+/// The format of a [`CanvasFont`]'s file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FontFormat {
+    /// A TrueType font (`.ttf`).
+    TrueType,
+    /// An OpenType font with PostScript outlines (`.otf`).
+    OpenType,
+    /// A compressed Web Open Font Format font (`.woff`).
+    Woff,
+    /// A Web Open Font Format 2 font (`.woff2`).
+    Woff2,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_are_recognized() {
+        let font = |magic: &[u8]| CanvasFont::from_bytes("Test", magic.to_vec()).format();
+        assert_eq!(font(&[0, 1, 0, 0, 9]), Some(FontFormat::TrueType));
+        assert_eq!(font(b"OTTO"), Some(FontFormat::OpenType));
+        assert_eq!(font(b"wOF2"), Some(FontFormat::Woff2));
+        assert_eq!(font(b"GIF8"), None);
+        assert_eq!(font(b"ab"), None);
+
+        let url = |url: &str| CanvasFont::from_url("Test", url).format();
+        assert_eq!(url("fonts/Inter.woff2?v=3"), Some(FontFormat::Woff2));
+        assert_eq!(url("/Inter.TTF"), Some(FontFormat::TrueType));
+        assert_eq!(url("https://example.com/font"), None);
+    }
+}
@@ -0,0 +1,427 @@
+use std::{f32::consts::PI, time::Duration};
+
+use gooey::core::{
+    figures::{Point, Vector},
+    Scaled,
+};
+
+use crate::{PointerEvent, PointerEventKind};
+
+/// How long a pointer must be held still to be a long press.
+pub const LONG_PRESS_DURATION: Duration = Duration::from_millis(500);
+/// The longest time between two taps that makes them a double tap.
+pub const DOUBLE_TAP_INTERVAL: Duration = Duration::from_millis(300);
+/// How far a pointer may move, in canvas coordinates, and still be a tap or
+/// long press.
+pub const TAP_SLOP: f32 = 10.;
+
+/// A gesture recognized from the pointer input of a
+/// [`Canvas`](crate::Canvas).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GestureEvent {
+    /// The gesture.
+    pub kind: GestureKind,
+    /// Where the gesture happened, relative to the canvas. For two-finger
+    /// gestures this is the point between the fingers.
+    pub location: Point<f32, Scaled>,
+}
+
+/// The kind of a [`GestureEvent`].
+///
+/// Two-finger gestures are reported incrementally: each time a finger moves,
+/// the change since the previous event is reported.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GestureKind {
+    /// The fingers moved apart or together. Multiply the current zoom by the
+    /// contained factor.
+    Pinch(f32),
+    /// The fingers rotated around each other by the contained angle, in
+    /// radians. Positive angles are clockwise.
+    Rotate(f32),
+    /// The point between the fingers moved by the contained amount.
+    Pan(Vector<f32, Scaled>),
+    /// A pointer was held still for [`LONG_PRESS_DURATION`].
+    LongPress,
+    /// A pointer was tapped twice within [`DOUBLE_TAP_INTERVAL`].
+    DoubleTap,
+}
+
+/// Turns pointer events into [`GestureEvent`]s. Every
+/// [`Canvas`](crate::Canvas) has one, which delivers its gestures to
+/// [`Renderable::gesture()`](crate::Renderable::gesture).
+#[derive(Debug, Default)]
+pub struct GestureRecognizer {
+    /// The pointers that are down, in the order they were pressed.
+    pointers: Vec<(u32, Point<f32, Scaled>)>,
+    /// The single press that may become a tap or long press.
+    press: Option<Press>,
+    /// The previous tap, which may become a double tap.
+    last_tap: Option<(Duration, Point<f32, Scaled>)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Press {
+    started: Duration,
+    location: Point<f32, Scaled>,
+    long_pressed: bool,
+}
+
+impl GestureRecognizer {
+    /// Updates the recognizer with `event`, which happened at `timestamp`,
+    /// and returns the gestures it completes. Timestamps only need to be
+    /// measured from a consistent origin.
+    pub fn pointer(&mut self, event: &PointerEvent, timestamp: Duration) -> Vec<GestureEvent> {
+        let mut gestures = Vec::new();
+        match event.kind {
+            PointerEventKind::Down(_) => {
+                self.pointers.retain(|(id, _)| *id != event.pointer_id);
+                self.pointers.push((event.pointer_id, event.location));
+                self.press = if self.pointers.len() == 1 {
+                    Some(Press {
+                        started: timestamp,
+                        location: event.location,
+                        long_pressed: false,
+                    })
+                } else {
+                    // A second finger turns the press into a two-finger
+                    // gesture.
+                    self.last_tap = None;
+                    None
+                };
+            }
+            PointerEventKind::Move => {
+                let previous = self.two_fingers();
+                if let Some((_, location)) = self
+                    .pointers
+                    .iter_mut()
+                    .find(|(id, _)| *id == event.pointer_id)
+                {
+                    *location = event.location;
+                } else {
+                    return gestures;
+                }
+
+                if let Some(press) = &self.press {
+                    if distance(press.location, event.location) > TAP_SLOP {
+                        self.press = None;
+                        self.last_tap = None;
+                    }
+                }
+
+                if let (Some(previous), Some(current)) = (previous, self.two_fingers()) {
+                    two_finger_gestures(previous, current, &mut gestures);
+                }
+            }
+            PointerEventKind::Up(_) => {
+                let count = self.pointers.len();
+                self.pointers.retain(|(id, _)| *id != event.pointer_id);
+                if count == self.pointers.len() {
+                    return gestures;
+                }
+
+                if let Some(press) = self.press.take() {
+                    if !press.long_pressed {
+                        gestures.extend(self.tap(timestamp, press.location));
+                    }
+                }
+            }
+            PointerEventKind::Cancel => {
+                self.pointers.retain(|(id, _)| *id != event.pointer_id);
+                self.press = None;
+                self.last_tap = None;
+            }
+            PointerEventKind::Wheel(_) => {}
+        }
+        gestures
+    }
+
+    /// Returns the long press gesture if the current press has been held long
+    /// enough by `now`.
+    pub fn poll(&mut self, now: Duration) -> Option<GestureEvent> {
+        let press = self.press.as_mut()?;
+        if press.long_pressed || now.checked_sub(press.started)? < LONG_PRESS_DURATION {
+            return None;
+        }
+
+        press.long_pressed = true;
+        self.last_tap = None;
+        Some(GestureEvent {
+            kind: GestureKind::LongPress,
+            location: press.location,
+        })
+    }
+
+    /// Returns true if [`poll()`](Self::poll) may recognize a long press in
+    /// the future.
+    #[must_use]
+    pub fn is_pending(&self) -> bool {
+        self.press.map_or(false, |press| !press.long_pressed)
+    }
+
+    fn tap(&mut self, timestamp: Duration, location: Point<f32, Scaled>) -> Option<GestureEvent> {
+        match self.last_tap.take() {
+            Some((tapped, tap_location))
+                if timestamp.checked_sub(tapped).unwrap_or_default() <= DOUBLE_TAP_INTERVAL
+                    && distance(tap_location, location) <= TAP_SLOP * 2. =>
+            {
+                Some(GestureEvent {
+                    kind: GestureKind::DoubleTap,
+                    location,
+                })
+            }
+            _ => {
+                self.last_tap = Some((timestamp, location));
+                None
+            }
+        }
+    }
+
+    fn two_fingers(&self) -> Option<(Point<f32, Scaled>, Point<f32, Scaled>)> {
+        match self.pointers.as_slice() {
+            [(_, first), (_, second), ..] => Some((*first, *second)),
+            _ => None,
+        }
+    }
+}
+
+fn two_finger_gestures(
+    previous: (Point<f32, Scaled>, Point<f32, Scaled>),
+    current: (Point<f32, Scaled>, Point<f32, Scaled>),
+    gestures: &mut Vec<GestureEvent>,
+) {
+    let (previous_center, current_center) = (midpoint(previous), midpoint(current));
+    let location = current_center;
+
+    let pan = Vector::new(
+        current_center.x - previous_center.x,
+        current_center.y - previous_center.y,
+    );
+    if pan.x != 0. || pan.y != 0. {
+        gestures.push(GestureEvent {
+            kind: GestureKind::Pan(pan),
+            location,
+        });
+    }
+
+    let previous_distance = distance(previous.0, previous.1);
+    let current_distance = distance(current.0, current.1);
+    if previous_distance > 0. && (current_distance - previous_distance).abs() > f32::EPSILON {
+        gestures.push(GestureEvent {
+            kind: GestureKind::Pinch(current_distance / previous_distance),
+            location,
+        });
+    }
+
+    let mut rotation = angle(current) - angle(previous);
+    // Take the shortest way around.
+    if rotation > PI {
+        rotation -= 2. * PI;
+    } else if rotation < -PI {
+        rotation += 2. * PI;
+    }
+    if rotation.abs() > f32::EPSILON {
+        gestures.push(GestureEvent {
+            kind: GestureKind::Rotate(rotation),
+            location,
+        });
+    }
+}
+
+fn midpoint((a, b): (Point<f32, Scaled>, Point<f32, Scaled>)) -> Point<f32, Scaled> {
+    Point::new((a.x + b.x) / 2., (a.y + b.y) / 2.)
+}
+
+fn angle((a, b): (Point<f32, Scaled>, Point<f32, Scaled>)) -> f32 {
+    (b.y - a.y).atan2(b.x - a.x)
+}
+
+fn distance(a: Point<f32, Scaled>, b: Point<f32, Scaled>) -> f32 {
+    (b.x - a.x).hypot(b.y - a.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Modifiers, PointerButton, PointerDevice};
+
+    fn finger(pointer_id: u32, kind: PointerEventKind, x: f32, y: f32) -> PointerEvent {
+        PointerEvent {
+            kind,
+            location: Point::new(x, y),
+            pointer_id,
+            device: PointerDevice::Touch,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    fn down(pointer_id: u32, x: f32, y: f32) -> PointerEvent {
+        finger(
+            pointer_id,
+            PointerEventKind::Down(PointerButton::Primary),
+            x,
+            y,
+        )
+    }
+
+    fn up(pointer_id: u32, x: f32, y: f32) -> PointerEvent {
+        finger(
+            pointer_id,
+            PointerEventKind::Up(PointerButton::Primary),
+            x,
+            y,
+        )
+    }
+
+    fn moved(pointer_id: u32, x: f32, y: f32) -> PointerEvent {
+        finger(pointer_id, PointerEventKind::Move, x, y)
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    /// Returns a recognizer with two fingers down, 20 apart horizontally
+    /// around (50, 50).
+    fn two_fingers() -> GestureRecognizer {
+        let mut recognizer = GestureRecognizer::default();
+        assert!(recognizer.pointer(&down(0, 40., 50.), ms(0)).is_empty());
+        assert!(recognizer.pointer(&down(1, 60., 50.), ms(0)).is_empty());
+        recognizer
+    }
+
+    fn kinds(gestures: &[GestureEvent]) -> Vec<GestureKind> {
+        gestures.iter().map(|gesture| gesture.kind).collect()
+    }
+
+    #[test]
+    fn spreading_fingers_pinches() {
+        let mut recognizer = two_fingers();
+        let gestures = recognizer.pointer(&moved(1, 80., 50.), ms(10));
+        // The midpoint moved too, so a pan accompanies the pinch.
+        assert_eq!(
+            kinds(&gestures),
+            vec![
+                GestureKind::Pan(Vector::new(10., 0.)),
+                GestureKind::Pinch(2.)
+            ]
+        );
+        assert_eq!(gestures[1].location, Point::new(60., 50.));
+    }
+
+    /// Moves both fingers one at a time, returning the gestures recognized
+    /// along the way.
+    fn move_both(
+        recognizer: &mut GestureRecognizer,
+        first: (f32, f32),
+        second: (f32, f32),
+    ) -> Vec<GestureEvent> {
+        let mut gestures = recognizer.pointer(&moved(0, first.0, first.1), ms(10));
+        gestures.extend(recognizer.pointer(&moved(1, second.0, second.1), ms(20)));
+        gestures
+    }
+
+    /// Returns the total pan, pinch and rotation of `gestures`.
+    fn totals(gestures: &[GestureEvent]) -> (Vector<f32, Scaled>, f32, f32) {
+        let mut totals = (Vector::default(), 1., 0.);
+        for gesture in gestures {
+            match gesture.kind {
+                GestureKind::Pan(delta) => {
+                    totals.0 = Vector::new(totals.0.x + delta.x, totals.0.y + delta.y);
+                }
+                GestureKind::Pinch(factor) => totals.1 *= factor,
+                GestureKind::Rotate(angle) => totals.2 += angle,
+                GestureKind::LongPress | GestureKind::DoubleTap => unreachable!(),
+            }
+        }
+        totals
+    }
+
+    #[test]
+    fn circling_fingers_rotate() {
+        let mut recognizer = two_fingers();
+        // A quarter turn clockwise around their midpoint.
+        let (pan, pinch, rotation) = totals(&move_both(&mut recognizer, (50., 40.), (50., 60.)));
+        assert!((rotation - PI / 2.).abs() < 1e-5, "{}", rotation);
+        assert!((pinch - 1.).abs() < 1e-5, "{}", pinch);
+        assert!(pan.x.abs() < 1e-5 && pan.y.abs() < 1e-5, "{:?}", pan);
+    }
+
+    #[test]
+    fn moving_fingers_together_pans() {
+        let mut recognizer = two_fingers();
+        let (pan, pinch, rotation) = totals(&move_both(&mut recognizer, (40., 60.), (60., 60.)));
+        assert_eq!(pan, Vector::new(0., 10.));
+        assert!((pinch - 1.).abs() < 1e-5, "{}", pinch);
+        assert!(rotation.abs() < 1e-5, "{}", rotation);
+    }
+
+    #[test]
+    fn holding_still_long_presses() {
+        let mut recognizer = GestureRecognizer::default();
+        recognizer.pointer(&down(0, 10., 10.), ms(0));
+        assert!(recognizer.is_pending());
+        assert_eq!(recognizer.poll(LONG_PRESS_DURATION - ms(1)), None);
+        assert_eq!(
+            recognizer.poll(LONG_PRESS_DURATION),
+            Some(GestureEvent {
+                kind: GestureKind::LongPress,
+                location: Point::new(10., 10.),
+            })
+        );
+        assert!(!recognizer.is_pending());
+        assert_eq!(recognizer.poll(LONG_PRESS_DURATION * 2), None);
+        // Releasing a long press isn't a tap.
+        recognizer.pointer(&up(0, 10., 10.), ms(600));
+        recognizer.pointer(&down(0, 10., 10.), ms(650));
+        assert!(recognizer.pointer(&up(0, 10., 10.), ms(700)).is_empty());
+    }
+
+    #[test]
+    fn tapping_twice_double_taps() {
+        let mut recognizer = GestureRecognizer::default();
+        recognizer.pointer(&down(0, 10., 10.), ms(0));
+        assert!(recognizer.pointer(&up(0, 10., 10.), ms(50)).is_empty());
+        recognizer.pointer(&down(0, 12., 10.), ms(100));
+        assert_eq!(
+            kinds(&recognizer.pointer(&up(0, 12., 10.), ms(150))),
+            vec![GestureKind::DoubleTap]
+        );
+
+        // Taps too far apart in time aren't a double tap.
+        recognizer.pointer(&down(0, 10., 10.), ms(1000));
+        recognizer.pointer(&up(0, 10., 10.), ms(1050));
+        recognizer.pointer(&down(0, 10., 10.), ms(1050) + DOUBLE_TAP_INTERVAL);
+        assert!(recognizer
+            .pointer(&up(0, 10., 10.), ms(1100) + DOUBLE_TAP_INTERVAL)
+            .is_empty());
+    }
+
+    #[test]
+    fn moving_past_the_slop_isnt_a_tap() {
+        let mut recognizer = GestureRecognizer::default();
+        recognizer.pointer(&down(0, 10., 10.), ms(0));
+        recognizer.pointer(&moved(0, 10. + TAP_SLOP / 2., 10.), ms(10));
+        assert!(recognizer.is_pending());
+        recognizer.pointer(&moved(0, 10. + TAP_SLOP * 2., 10.), ms(20));
+        assert!(!recognizer.is_pending());
+        assert_eq!(recognizer.poll(LONG_PRESS_DURATION), None);
+        recognizer.pointer(&up(0, 30., 10.), ms(30));
+        recognizer.pointer(&down(0, 30., 10.), ms(40));
+        assert!(recognizer.pointer(&up(0, 30., 10.), ms(50)).is_empty());
+    }
+
+    #[test]
+    fn cancelling_forgets_the_press() {
+        let mut recognizer = GestureRecognizer::default();
+        recognizer.pointer(&down(0, 10., 10.), ms(0));
+        recognizer.pointer(&up(0, 10., 10.), ms(50));
+        recognizer.pointer(&down(0, 10., 10.), ms(100));
+        assert!(recognizer
+            .pointer(&finger(0, PointerEventKind::Cancel, 10., 10.), ms(150))
+            .is_empty());
+        assert!(!recognizer.is_pending());
+        assert_eq!(recognizer.poll(LONG_PRESS_DURATION), None);
+        // The pointer is no longer down, so its moves are ignored.
+        assert!(recognizer.pointer(&moved(0, 20., 10.), ms(200)).is_empty());
+    }
+}
@@ -1,19 +1,38 @@
-use std::boxed::Box;
+use std::{
+    boxed::Box,
+    cmp::Ordering,
+    f32::consts::{FRAC_PI_2, FRAC_PI_4, PI, SQRT_2},
+    sync::Once,
+    time::{Duration, Instant},
+};
 
 use gooey::{
-    core::{figures::Size, Scaled, Transmogrifier, TransmogrifierContext},
+    core::{
+        assets::Image,
+        figures::{
+            DisplayScale, Displayable, ExtentsRect, Point, Rect, Rectlike, Size, SizedRect, Vector,
+        },
+        styles::Color,
+        Context, Pixels, Scaled, Transmogrifier, TransmogrifierContext,
+    },
     frontends::{
         rasterizer::{
-            ContentArea, Rasterizer, RegisteredTransmogrifier, Renderer, WidgetRasterizer,
+            winit::event::MouseButton, ContentArea, EventStatus, Rasterizer,
+            RegisteredTransmogrifier, Renderer, WidgetRasterizer,
         },
         renderers::kludgine::Kludgine,
     },
+    renderer::StrokeOptions,
 };
 
-use crate::{Canvas, CanvasRenderer, CanvasTransmogrifier, Command};
+use crate::{
+    clip::ClipShape, effects::Effects, stroke::Dasher, Canvas, CanvasBackend, CanvasRenderer,
+    CanvasTransmogrifier, Command, ImageSmoothing, LineCap, LineJoin, Modifiers, PointerButton,
+    PointerDevice, PointerEvent, PointerEventKind, StrokeStyle, Texture, Transform,
+};
 
 impl Transmogrifier<Rasterizer<Kludgine>> for CanvasTransmogrifier {
-    type State = ();
+    type State = State;
     type Widget = Canvas;
 
     fn receive_command(
@@ -21,7 +40,45 @@ impl Transmogrifier<Rasterizer<Kludgine>> for CanvasTransmogrifier {
         command: Command,
         context: &mut TransmogrifierContext<'_, Self, Rasterizer<Kludgine>>,
     ) {
-        let Command::Refresh = command;
+        match command {
+            // Kludgine redraws the entire window each frame, so invalidating
+            // a region is the same as a refresh.
+            Command::Refresh | Command::Invalidate(_) => {}
+            Command::Message(message) => context.widget.renderable.receive(message),
+            Command::ShowStatistics(show) => context.widget.show_statistics = show,
+            Command::Capture(callback) => {
+                // Kludgine renders the whole window into the swap chain, and
+                // the rasterizer doesn't expose a way to read it back.
+                log::warn!("capturing a canvas isn't supported by the Kludgine frontend");
+                callback.invoke(None);
+                return;
+            }
+            Command::SetCursor(cursor) => {
+                // The rasterizer doesn't let widgets change the window's
+                // cursor, so it's only recorded.
+                context.widget.cursor = cursor;
+                return;
+            }
+            Command::Drag(event) => {
+                if !context.widget.handle_drag(event) {
+                    return;
+                }
+            }
+            Command::RegisterFont(font) => {
+                // The rasterizer doesn't let widgets add fonts to Kludgine, so
+                // text keeps using the default font.
+                log::warn!(
+                    "registering fonts isn't supported by the Kludgine frontend: {}",
+                    font.family()
+                );
+                return;
+            }
+        }
+        if context.state.redraw_pending {
+            context.widget.statistics.record_coalesced_redraw();
+        } else {
+            context.state.redraw_pending = true;
+        }
         context.frontend.set_needs_redraw();
     }
 }
@@ -32,10 +89,121 @@ impl WidgetRasterizer<Kludgine> for CanvasTransmogrifier {
         context: &mut TransmogrifierContext<'_, Self, Rasterizer<Kludgine>>,
         content_area: &ContentArea,
     ) {
+        if !context.state.initialized {
+            context.state.initialized = true;
+            let widget_context = Context::from(&*context);
+            context.widget.renderable.initialize(&widget_context);
+            for font in &context.widget.fonts {
+                log::warn!(
+                    "registering fonts isn't supported by the Kludgine frontend: {}",
+                    font.family()
+                );
+            }
+        }
+
+        let size = content_area.size.content;
+        if context.state.size != Some(size) {
+            context.state.size = Some(size);
+            context.widget.renderable.resized(size);
+        }
+
+        let now = Instant::now();
+        let elapsed = context
+            .state
+            .last_frame
+            .take()
+            .map_or_else(Duration::default, |last_frame| now - last_frame);
+        let timestamp = context.state.timestamp();
+        context.widget.poll_gestures(timestamp);
+        // Keep drawing frames while waiting to recognize a long press.
+        let needs_frame =
+            context.widget.renderable.update(elapsed) || context.widget.gestures.is_pending();
+        context.state.redraw_pending = false;
+        let since_last_frame = context
+            .state
+            .last_drawn
+            .replace(now)
+            .map(|last_drawn| now - last_drawn);
+
         if let Some(scene) = context.frontend.renderer() {
-            context.widget.renderable.render(
-                CanvasRenderer::RasterizerRenderer(scene.clone()),
+            // Keep the canvas from drawing over neighboring widgets.
+            context.widget.render_frame(
+                CanvasRenderer::new(CanvasBackend::RasterizerRenderer(scene.clone()), None)
+                    .clip_to(Rect::from(SizedRect::new(content_area.location, size))),
                 content_area,
+                since_last_frame,
+                || now.elapsed(),
+            );
+        }
+
+        if needs_frame {
+            context.state.last_frame = Some(now);
+            context.frontend.set_needs_redraw();
+        }
+    }
+
+    fn hit_test(
+        &self,
+        _context: &mut TransmogrifierContext<'_, Self, Rasterizer<Kludgine>>,
+        location: Point<f32, Scaled>,
+        area: &ContentArea,
+    ) -> bool {
+        let size = area.size.content;
+        location.x >= area.location.x
+            && location.y >= area.location.y
+            && location.x < area.location.x + size.width
+            && location.y < area.location.y + size.height
+    }
+
+    fn mouse_down(
+        &self,
+        context: &mut TransmogrifierContext<'_, Self, Rasterizer<Kludgine>>,
+        button: MouseButton,
+        location: Point<f32, Scaled>,
+        area: &ContentArea,
+    ) -> EventStatus {
+        let handled = dispatch_pointer(
+            context,
+            PointerEventKind::Down(PointerButton::from(button)),
+            location,
+            area,
+        );
+        // Accepting the press has the drag and release delivered to this
+        // canvas, so it's only accepted if something wants to track it.
+        if handled || context.widget.on_event.is_some() {
+            EventStatus::Processed
+        } else {
+            // The release won't be delivered, so the gesture recognizer
+            // mustn't wait for it.
+            dispatch_pointer(context, PointerEventKind::Cancel, location, area);
+            EventStatus::Ignored
+        }
+    }
+
+    fn mouse_drag(
+        &self,
+        context: &mut TransmogrifierContext<'_, Self, Rasterizer<Kludgine>>,
+        _button: MouseButton,
+        location: Point<f32, Scaled>,
+        area: &ContentArea,
+    ) {
+        dispatch_pointer(context, PointerEventKind::Move, location, area);
+    }
+
+    fn mouse_up(
+        &self,
+        context: &mut TransmogrifierContext<'_, Self, Rasterizer<Kludgine>>,
+        button: MouseButton,
+        location: Option<Point<f32, Scaled>>,
+        area: &ContentArea,
+    ) {
+        let location = location.or(context.state.last_pointer_location);
+        if let Some(location) = location {
+            dispatch_pointer(
+                context,
+                PointerEventKind::Up(PointerButton::from(button)),
+                location,
+                area,
             );
         }
     }
@@ -56,8 +224,846 @@ impl WidgetRasterizer<Kludgine> for CanvasTransmogrifier {
     }
 }
 
+/// Draws regions of `image` at their natural size by clipping the entire
+/// image to each region.
+pub(crate) fn draw_image_regions(
+    renderer: &Kludgine,
+    image: &Image,
+    regions: impl Iterator<Item = (Rect<u32, Pixels>, Point<f32, Scaled>)>,
+) {
+    let scale = renderer.scale();
+    for (source, location) in regions {
+        let source = source.cast::<f32>().to_scaled(&scale).as_sized();
+        renderer
+            .clip_to(Rect::from(SizedRect::new(location, source.size)))
+            .draw_image(
+                image,
+                Point::<f32, Scaled>::new(
+                    location.x - source.origin.x,
+                    location.y - source.origin.y,
+                ),
+            );
+    }
+}
+
+/// Warns, the first time a level of smoothing other than the default is
+/// requested, that Kludgine can't change how images are sampled.
+pub(crate) fn warn_image_smoothing(smoothing: ImageSmoothing) {
+    static WARNED: Once = Once::new();
+    if smoothing != ImageSmoothing::default() {
+        WARNED.call_once(|| {
+            log::warn!("image smoothing levels aren't supported by the Kludgine frontend");
+        });
+    }
+}
+
+/// Draws `texture` with its top-left corner at `location`. Kludgine can't
+/// create textures from pixels at runtime, so each rectangle of same-colored
+/// pixels is filled separately, casting its part of the texture's shadow and
+/// blurred like any other filled rectangle.
+pub(crate) fn draw_texture(
+    renderer: &Kludgine,
+    texture: &Texture,
+    location: Point<f32, Scaled>,
+    effects: &Effects,
+) {
+    let pixel = Size::<f32, Pixels>::new(1., 1.).to_scaled(&renderer.scale());
+    let rects = texture.pixel_rects();
+    let rects = rects.iter().map(|rect| {
+        let [red, green, blue, alpha] = rect.rgba;
+        (
+            Rect::from(SizedRect::new(
+                Point::new(
+                    location.x + rect.x as f32 * pixel.width,
+                    location.y + rect.y as f32 * pixel.height,
+                ),
+                Size::new(
+                    rect.width as f32 * pixel.width,
+                    rect.height as f32 * pixel.height,
+                ),
+            )),
+            Color::new(
+                f32::from(red) / 255.,
+                f32::from(green) / 255.,
+                f32::from(blue) / 255.,
+                f32::from(alpha) / 255.,
+            ),
+        )
+    });
+    // The whole shadow is drawn first so that it stays beneath the texture.
+    for (rect, color) in rects.clone() {
+        draw_shadow(effects, color, &|offset, color| {
+            renderer.fill_rect(&Transform::new(1., offset).transform_rect(rect), color);
+        });
+    }
+    for (rect, color) in rects {
+        draw_blurred(effects, color, &|offset, color| {
+            renderer.fill_rect(&Transform::new(1., offset).transform_rect(rect), color);
+        });
+    }
+}
+
+/// Strokes the lines joining `points` with `style`. When `closed` is true, the
+/// last point must repeat the first. The renderer doesn't expose Kludgine's
+/// tessellator, so each line is stroked separately, and the joins and round
+/// caps between them are filled as polygons.
+pub(crate) fn stroke_polyline(
+    renderer: &Kludgine,
+    points: &[Point<f32, Scaled>],
+    closed: bool,
+    options: &StrokeOptions,
+    style: &StrokeStyle,
+) {
+    match style.dash_pattern() {
+        Some(pattern) => {
+            for dash in Dasher::new(pattern, style.dash_offset).dashes(points) {
+                stroke_joined(renderer, &dash, false, options, style);
+            }
+        }
+        None => stroke_joined(renderer, points, closed, options, style),
+    }
+}
+
+/// Strokes the lines joining `points`, joining their corners and capping the
+/// ends of the polyline unless it is `closed`.
+fn stroke_joined(
+    renderer: &Kludgine,
+    points: &[Point<f32, Scaled>],
+    closed: bool,
+    options: &StrokeOptions,
+    style: &StrokeStyle,
+) {
+    // Repeated points don't have a direction to join or cap.
+    let mut points = points.to_vec();
+    points.dedup();
+    if closed && points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    let half_width = options.line_width.get() / 2.;
+
+    let lines = if closed {
+        points.len()
+    } else {
+        points.len().saturating_sub(1)
+    };
+    for index in 0..lines {
+        renderer.stroke_line(points[index], points[(index + 1) % points.len()], options);
+    }
+
+    if closed {
+        if points.len() > 2 {
+            for index in 0..points.len() {
+                let previous = points[(index + points.len() - 1) % points.len()];
+                let next = points[(index + 1) % points.len()];
+                let join = join_polygon(previous, points[index], next, half_width, style);
+                fill_polygon(renderer, join, options.color);
+            }
+        }
+        return;
+    }
+    for corner in points.windows(3) {
+        let join = join_polygon(corner[0], corner[1], corner[2], half_width, style);
+        fill_polygon(renderer, join, options.color);
+    }
+    if let [first, second, ..] = points.as_slice() {
+        fill_cap(
+            renderer,
+            *first,
+            direction(*second, *first),
+            half_width,
+            options,
+            style.cap,
+        );
+    }
+    if let [.., second_to_last, last] = points.as_slice() {
+        fill_cap(
+            renderer,
+            *last,
+            direction(*second_to_last, *last),
+            half_width,
+            options,
+            style.cap,
+        );
+    }
+}
+
+/// Returns the polygon joining the outside of the corner at `corner` between
+/// the lines from `previous` and to `next`, according to `style`. Straight
+/// corners return an empty polygon.
+fn join_polygon(
+    previous: Point<f32, Scaled>,
+    corner: Point<f32, Scaled>,
+    next: Point<f32, Scaled>,
+    half_width: f32,
+    style: &StrokeStyle,
+) -> Vec<Point<f32, Scaled>> {
+    let (incoming, outgoing) = (direction(previous, corner), direction(corner, next));
+    let turn = incoming.x * outgoing.y - incoming.y * outgoing.x;
+    if turn.abs() <= f32::EPSILON {
+        return Vec::new();
+    }
+    // The sides of the lines on the outside of the corner.
+    let side = -turn.signum();
+    let first = Vector::new(-incoming.y * side, incoming.x * side);
+    let second = Vector::new(-outgoing.y * side, outgoing.x * side);
+    let offset = |vector: Vector<f32, Scaled>, distance: f32| {
+        Point::new(
+            corner.x + vector.x * distance,
+            corner.y + vector.y * distance,
+        )
+    };
+
+    match style.join {
+        LineJoin::Round => {
+            let start = first.y.atan2(first.x);
+            let sweep = (first.x * second.y - first.y * second.x)
+                .atan2(first.x * second.x + first.y * second.y);
+            let mut polygon = vec![corner];
+            polygon.extend(arc(corner, half_width, start, sweep));
+            polygon
+        }
+        LineJoin::Miter => {
+            let bisector = direction(
+                Point::default(),
+                Point::new(first.x + second.x, first.y + second.y),
+            );
+            let cos = bisector.x * first.x + bisector.y * first.y;
+            // The miter's length, as a multiple of the stroke width.
+            let ratio = 1. / cos;
+            if cos > 0. && ratio <= style.miter_limit {
+                vec![
+                    corner,
+                    offset(first, half_width),
+                    offset(bisector, half_width * ratio),
+                    offset(second, half_width),
+                ]
+            } else {
+                vec![
+                    corner,
+                    offset(first, half_width),
+                    offset(second, half_width),
+                ]
+            }
+        }
+        LineJoin::Bevel => vec![
+            corner,
+            offset(first, half_width),
+            offset(second, half_width),
+        ],
+    }
+}
+
+/// Draws `cap` beyond `end`, where the stroke points along `direction`.
+fn fill_cap(
+    renderer: &Kludgine,
+    end: Point<f32, Scaled>,
+    direction: Vector<f32, Scaled>,
+    half_width: f32,
+    options: &StrokeOptions,
+    cap: LineCap,
+) {
+    match cap {
+        LineCap::Butt => {}
+        LineCap::Square => renderer.stroke_line(
+            end,
+            Point::new(
+                end.x + direction.x * half_width,
+                end.y + direction.y * half_width,
+            ),
+            options,
+        ),
+        LineCap::Round => {
+            let start = (-direction.x).atan2(direction.y);
+            fill_polygon(
+                renderer,
+                arc(end, half_width, start, PI).collect(),
+                options.color,
+            );
+        }
+    }
+}
+
+/// Returns the unit vector pointing from `from` to `to`, or zero if they're
+/// the same point.
+fn direction(from: Point<f32, Scaled>, to: Point<f32, Scaled>) -> Vector<f32, Scaled> {
+    let length = (to.x - from.x).hypot(to.y - from.y);
+    if length > 0. {
+        Vector::new((to.x - from.x) / length, (to.y - from.y) / length)
+    } else {
+        Vector::default()
+    }
+}
+
+/// Returns points along the arc around `center` that starts at the angle
+/// `start` and turns by `sweep`, both in radians.
+fn arc(
+    center: Point<f32, Scaled>,
+    radius: f32,
+    start: f32,
+    sweep: f32,
+) -> impl Iterator<Item = Point<f32, Scaled>> {
+    let segments = ((sweep.abs() / FRAC_PI_2).ceil() as usize * CORNER_SEGMENTS).max(1);
+    (0..=segments).map(move |step| {
+        let angle = start + sweep * step as f32 / segments as f32;
+        Point::new(
+            center.x + radius * angle.cos(),
+            center.y + radius * angle.sin(),
+        )
+    })
+}
+
+/// Fills `polygon` with `color`, one pixel row at a time.
+fn fill_polygon(renderer: &Kludgine, polygon: Vec<Point<f32, Scaled>>, color: Color) {
+    if polygon.len() < 3 {
+        return;
+    }
+    let spans = clip_spans(
+        &ClipShape::Polygon(polygon),
+        renderer.clip_bounds(),
+        &renderer.scale(),
+    );
+    for span in spans {
+        renderer.fill_rect(&span, color);
+    }
+}
+
+/// The most rings of samples used to approximate a blur.
+const MAX_BLUR_RINGS: usize = 4;
+
+/// Calls `draw` with the offsets and colors needed to draw a shape of `color`
+/// with `effects`. Kludgine doesn't let widgets render into offscreen
+/// targets, so blurs are approximated by drawing translucent copies of the
+/// shape around its location.
+pub(crate) fn draw_with_effects<F: Fn(Vector<f32, Scaled>, Color)>(
+    effects: &Effects,
+    color: Color,
+    draw: F,
+) {
+    draw_shadow(effects, color, &draw);
+    draw_blurred(effects, color, &draw);
+}
+
+/// Calls `draw` with the offsets and colors needed to draw the shadow of a
+/// shape of `color`, if `effects` has one.
+fn draw_shadow<F: Fn(Vector<f32, Scaled>, Color)>(effects: &Effects, color: Color, draw: &F) {
+    if let Some(shadow) = &effects.shadow {
+        let samples = blur_samples(shadow.blur_radius);
+        let alpha = sample_alpha(shadow.color.alpha() * color.alpha(), samples.len());
+        let shadow_color = shadow.color.with_alpha(alpha);
+        for sample in samples {
+            draw(
+                Vector::new(shadow.offset.x + sample.x, shadow.offset.y + sample.y),
+                shadow_color,
+            );
+        }
+    }
+}
+
+/// Calls `draw` with the offsets and colors needed to draw a shape of `color`
+/// with the blur in `effects`.
+fn draw_blurred<F: Fn(Vector<f32, Scaled>, Color)>(effects: &Effects, color: Color, draw: &F) {
+    let samples = blur_samples(effects.blur_radius);
+    let color = color.with_alpha(sample_alpha(color.alpha(), samples.len()));
+    for sample in samples {
+        draw(sample, color);
+    }
+}
+
+/// Returns the offsets to draw copies of a shape at to approximate a blur of
+/// `radius`.
+fn blur_samples(radius: f32) -> Vec<Vector<f32, Scaled>> {
+    let mut samples = vec![Vector::default()];
+    if radius > 0. {
+        let rings = ((radius / 2.).ceil() as usize).max(1).min(MAX_BLUR_RINGS);
+        for ring in 1..=rings {
+            let distance = radius * ring as f32 / rings as f32;
+            for step in 0..8 {
+                // Stagger each ring so that the copies don't line up.
+                let angle = (step as f32 + ring as f32 / 2.) * FRAC_PI_4;
+                samples.push(Vector::new(distance * angle.cos(), distance * angle.sin()));
+            }
+        }
+    }
+    samples
+}
+
+/// The most opaque the center of a blurred shape is drawn. Copies of an
+/// opaque shape would each need to be opaque to blend to an opaque center,
+/// which would grow the shape instead of blurring its edges.
+const MAX_BLURRED_ALPHA: f32 = 0.99;
+
+/// Returns the alpha that `count` overlapping copies need to blend to
+/// `alpha`. Where fewer copies overlap, towards the edges of a blurred shape,
+/// they blend to less.
+fn sample_alpha(alpha: f32, count: usize) -> f32 {
+    let alpha = alpha.max(0.).min(1.);
+    if count <= 1 {
+        return alpha;
+    }
+    1. - (1. - alpha.min(MAX_BLURRED_ALPHA)).powf(1. / count as f32)
+}
+
+/// The number of segments used to approximate each corner of a rounded
+/// rectangle.
+const CORNER_SEGMENTS: usize = 8;
+
+/// Kludgine only supports rectangular clipping, so a non-rectangular clip is
+/// approximated by rectangles one pixel tall that together cover `shape`
+/// within `clip`. Consecutive rows with the same spans are merged.
+pub(crate) fn clip_spans(
+    shape: &ClipShape,
+    clip: Rect<f32, Scaled>,
+    scale: &DisplayScale<f32>,
+) -> Vec<Rect<f32, Scaled>> {
+    let bounds = match intersect(shape.bounds(), clip) {
+        Some(bounds) => bounds.as_extents(),
+        None => return Vec::new(),
+    };
+    let polygon = polygon(shape);
+    let row_height = Size::<f32, Pixels>::new(1., 1.).to_scaled(scale).height;
+
+    let mut spans = Vec::new();
+    // The y coordinate where the current run of identical rows started.
+    let mut current: Option<(f32, Vec<(f32, f32)>)> = None;
+    let mut y = (bounds.origin.y / row_height).floor() * row_height;
+    while y < bounds.extent.y {
+        let row = row_spans(
+            &polygon,
+            y + row_height / 2.,
+            bounds.origin.x,
+            bounds.extent.x,
+        );
+        let same_as_previous = current
+            .as_ref()
+            .map_or(false, |(_, previous)| *previous == row);
+        if !same_as_previous {
+            if let Some((start, previous)) = current.replace((y, row)) {
+                push_spans(&mut spans, &previous, start, y, &bounds);
+            }
+        }
+        y += row_height;
+    }
+    if let Some((start, previous)) = current {
+        push_spans(&mut spans, &previous, start, y, &bounds);
+    }
+    spans
+}
+
+/// Returns the intersection of every span in `a` with every span in `b`.
+pub(crate) fn intersect_spans(
+    a: &[Rect<f32, Scaled>],
+    b: &[Rect<f32, Scaled>],
+) -> Vec<Rect<f32, Scaled>> {
+    a.iter()
+        .flat_map(|a| b.iter().filter_map(move |b| intersect(*a, *b)))
+        .collect()
+}
+
+/// Returns the intersection of two rectangles, or `None` if they don't
+/// overlap.
+pub(crate) fn intersect(a: Rect<f32, Scaled>, b: Rect<f32, Scaled>) -> Option<Rect<f32, Scaled>> {
+    let (a, b) = (a.as_extents(), b.as_extents());
+    let origin = Point::new(a.origin.x.max(b.origin.x), a.origin.y.max(b.origin.y));
+    let extent = Point::new(a.extent.x.min(b.extent.x), a.extent.y.min(b.extent.y));
+    if extent.x > origin.x && extent.y > origin.y {
+        Some(Rect::from(ExtentsRect::new(origin, extent)))
+    } else {
+        None
+    }
+}
+
+/// Returns `rect` grown by `amount` on every side.
+pub(crate) fn outset(rect: Rect<f32, Scaled>, amount: f32) -> Rect<f32, Scaled> {
+    let rect = rect.as_extents();
+    Rect::from(ExtentsRect::new(
+        Point::new(rect.origin.x - amount, rect.origin.y - amount),
+        Point::new(rect.extent.x + amount, rect.extent.y + amount),
+    ))
+}
+
+/// Returns how far beyond a shape `effects` may draw.
+pub(crate) fn effects_extent(effects: &Effects) -> f32 {
+    let shadow = effects.shadow.map_or(0., |shadow| {
+        shadow.offset.x.abs().max(shadow.offset.y.abs()) + shadow.blur_radius
+    });
+    shadow.max(effects.blur_radius)
+}
+
+/// Returns how far beyond its points a stroke with `options` and `style` may
+/// draw, including its caps and joins.
+pub(crate) fn stroke_extent(options: &StrokeOptions, style: &StrokeStyle) -> f32 {
+    options.line_width.get() / 2. * style.miter_limit.max(SQRT_2)
+}
+
+/// Returns the horizontal spans of `polygon` at `y`, using the even-odd rule.
+fn row_spans(polygon: &[Point<f32, Scaled>], y: f32, min_x: f32, max_x: f32) -> Vec<(f32, f32)> {
+    let mut crossings = Vec::new();
+    for (index, a) in polygon.iter().enumerate() {
+        let b = polygon[(index + 1) % polygon.len()];
+        if (a.y > y) != (b.y > y) {
+            let x = (b.x - a.x) * (y - a.y) / (b.y - a.y) + a.x;
+            // Edges with coordinates that aren't finite don't cross the row.
+            if x.is_finite() {
+                crossings.push(x);
+            }
+        }
+    }
+    crossings.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    crossings
+        .chunks_exact(2)
+        .map(|pair| (pair[0].max(min_x), pair[1].min(max_x)))
+        .filter(|(start, end)| end > start)
+        .collect()
+}
+
+fn push_spans(
+    spans: &mut Vec<Rect<f32, Scaled>>,
+    row: &[(f32, f32)],
+    top: f32,
+    bottom: f32,
+    bounds: &ExtentsRect<f32, Scaled>,
+) {
+    let (top, bottom) = (top.max(bounds.origin.y), bottom.min(bounds.extent.y));
+    spans.extend(row.iter().map(|(start, end)| {
+        Rect::from(ExtentsRect::new(
+            Point::new(*start, top),
+            Point::new(*end, bottom),
+        ))
+    }));
+}
+
+/// Returns the outline of `shape` as a polygon.
+fn polygon(shape: &ClipShape) -> Vec<Point<f32, Scaled>> {
+    match shape {
+        ClipShape::RoundedRect { rect, radius } => {
+            let rect = rect.as_extents();
+            let radius = *radius;
+            // Each corner's center, and the quarter turn its arc starts at,
+            // clockwise from the top-left corner.
+            let corners = [
+                (rect.origin.x + radius, rect.origin.y + radius, 2.),
+                (rect.extent.x - radius, rect.origin.y + radius, 3.),
+                (rect.extent.x - radius, rect.extent.y - radius, 0.),
+                (rect.origin.x + radius, rect.extent.y - radius, 1.),
+            ];
+            let mut points = Vec::with_capacity(corners.len() * (CORNER_SEGMENTS + 1));
+            for (center_x, center_y, quarter) in corners.iter() {
+                for step in 0..=CORNER_SEGMENTS {
+                    let angle = (quarter + step as f32 / CORNER_SEGMENTS as f32) * FRAC_PI_2;
+                    points.push(Point::new(
+                        center_x + radius * angle.cos(),
+                        center_y + radius * angle.sin(),
+                    ));
+                }
+            }
+            points
+        }
+        ClipShape::Polygon(points) => points.clone(),
+    }
+}
+
+/// Sends a pointer event at `location`, in window coordinates, to the canvas
+/// drawn in `area`. Returns true if the renderable or a gesture it recognized
+/// handled the event.
+fn dispatch_pointer(
+    context: &mut TransmogrifierContext<'_, CanvasTransmogrifier, Rasterizer<Kludgine>>,
+    kind: PointerEventKind,
+    location: Point<f32, Scaled>,
+    area: &ContentArea,
+) -> bool {
+    context.state.last_pointer_location = Some(location);
+    let event = PointerEvent {
+        kind,
+        location: Point::new(location.x - area.location.x, location.y - area.location.y),
+        // The rasterizer only reports the mouse, without the modifier keys.
+        pointer_id: 0,
+        device: PointerDevice::Mouse,
+        modifiers: Modifiers::default(),
+    };
+    let timestamp = context.state.timestamp();
+    let handled = context.widget.handle_pointer(event, timestamp);
+    if handled {
+        context.frontend.set_needs_redraw();
+    }
+    handled
+}
+
+impl From<MouseButton> for PointerButton {
+    fn from(button: MouseButton) -> Self {
+        match button {
+            MouseButton::Left => Self::Primary,
+            MouseButton::Right => Self::Secondary,
+            MouseButton::Middle => Self::Middle,
+            MouseButton::Other(index) => Self::Other(index),
+        }
+    }
+}
+
 impl From<CanvasTransmogrifier> for RegisteredTransmogrifier<Kludgine> {
     fn from(transmogrifier: CanvasTransmogrifier) -> Self {
         Self(Box::new(transmogrifier))
     }
 }
+
+#[derive(Debug, Default)]
+pub struct State {
+    initialized: bool,
+    size: Option<Size<f32, Scaled>>,
+    /// The time the previous frame was rendered, if it requested another frame.
+    last_frame: Option<Instant>,
+    /// The time the previous frame was drawn.
+    last_drawn: Option<Instant>,
+    /// True if a redraw has been requested since the previous frame.
+    redraw_pending: bool,
+    /// The most recent pointer location, in window coordinates.
+    last_pointer_location: Option<Point<f32, Scaled>>,
+    /// The origin of the timestamps given to the gesture recognizer.
+    epoch: Option<Instant>,
+}
+
+impl State {
+    /// Returns the time since the first timestamp was requested.
+    fn timestamp(&mut self) -> Duration {
+        self.epoch.get_or_insert_with(Instant::now).elapsed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gooey::core::figures::Scale;
+
+    use super::*;
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Rect<f32, Scaled> {
+        Rect::from(SizedRect::new(Point::new(x, y), Size::new(width, height)))
+    }
+
+    fn unscaled() -> DisplayScale<f32> {
+        DisplayScale::new(Scale::new(1.), Scale::new(1.))
+    }
+
+    /// Returns the edges of each of `spans`, so that spans can be compared
+    /// however they were created.
+    fn edges(spans: &[Rect<f32, Scaled>]) -> Vec<(f32, f32, f32, f32)> {
+        spans
+            .iter()
+            .map(|span| {
+                let span = span.as_extents();
+                (span.origin.x, span.origin.y, span.extent.x, span.extent.y)
+            })
+            .collect()
+    }
+
+    /// Returns the total area covered by `spans`, which must not overlap.
+    fn area(spans: &[Rect<f32, Scaled>]) -> f32 {
+        spans
+            .iter()
+            .map(|span| {
+                let span = span.as_sized();
+                span.size.width * span.size.height
+            })
+            .sum()
+    }
+
+    #[test]
+    fn rectangular_polygons_are_one_span() {
+        let shape = ClipShape::Polygon(vec![
+            Point::new(10., 10.),
+            Point::new(50., 10.),
+            Point::new(50., 30.),
+            Point::new(10., 30.),
+        ]);
+        let spans = clip_spans(&shape, rect(0., 0., 100., 100.), &unscaled());
+        assert_eq!(edges(&spans), vec![(10., 10., 50., 30.)]);
+    }
+
+    #[test]
+    fn spans_stay_within_the_clip() {
+        let shape = ClipShape::rounded_rect(rect(0., 0., 100., 100.), 10.);
+        let clip = rect(20., 20., 30., 30.);
+        let spans = clip_spans(&shape, clip, &unscaled());
+        assert!(!spans.is_empty());
+        let clip = clip.as_extents();
+        assert!(edges(&spans).into_iter().all(|(left, top, right, bottom)| {
+            left >= clip.origin.x
+                && top >= clip.origin.y
+                && right <= clip.extent.x
+                && bottom <= clip.extent.y
+        }));
+        assert!((area(&spans) - 900.).abs() < 0.01);
+    }
+
+    #[test]
+    fn rounded_corners_are_cut() {
+        let shape = ClipShape::rounded_rect(rect(0., 0., 40., 40.), 10.);
+        let spans = clip_spans(&shape, rect(0., 0., 100., 100.), &unscaled());
+        let covered = area(&spans);
+        // A square, less the area outside of its four quarter circles.
+        let expected = 40. * 40. - (4. - std::f32::consts::PI) * 10. * 10.;
+        assert!(
+            (covered - expected).abs() < 40.,
+            "{} != {}",
+            covered,
+            expected
+        );
+        // The corner pixel is outside of the clip, and the center is inside.
+        let contains = |x: f32, y: f32| {
+            spans.iter().any(|span| {
+                let span = span.as_extents();
+                x >= span.origin.x && x < span.extent.x && y >= span.origin.y && y < span.extent.y
+            })
+        };
+        assert!(!contains(0.5, 0.5));
+        assert!(contains(20., 20.));
+    }
+
+    #[test]
+    fn polygons_use_the_even_odd_rule() {
+        // A square with a square hole, drawn as one outline that doubles back.
+        let outline = vec![
+            Point::new(0., 0.),
+            Point::new(30., 0.),
+            Point::new(30., 30.),
+            Point::new(0., 30.),
+            Point::new(0., 0.),
+            Point::new(10., 10.),
+            Point::new(10., 20.),
+            Point::new(20., 20.),
+            Point::new(20., 10.),
+            Point::new(10., 10.),
+        ];
+        assert_eq!(
+            row_spans(&outline, 15., -100., 100.),
+            vec![(0., 10.), (20., 30.)]
+        );
+        // The edges into and out of the hole overlap, splitting the row.
+        assert_eq!(
+            row_spans(&outline, 5., -100., 100.),
+            vec![(0., 5.), (5., 30.)]
+        );
+    }
+
+    #[test]
+    fn coordinates_that_are_not_finite_are_ignored() {
+        let outline = vec![
+            Point::new(0., 0.),
+            Point::new(f32::NAN, 5.),
+            Point::new(10., 10.),
+            Point::new(0., 10.),
+        ];
+        // Doesn't panic.
+        drop(row_spans(&outline, 5., 0., 100.));
+        let shape = ClipShape::Polygon(outline);
+        drop(clip_spans(&shape, rect(0., 0., 100., 100.), &unscaled()));
+    }
+
+    #[test]
+    fn nested_clips_intersect() {
+        let outer = vec![rect(0., 0., 20., 10.), rect(0., 10., 10., 10.)];
+        let inner = vec![rect(5., 5., 30., 30.)];
+        assert_eq!(
+            edges(&intersect_spans(&outer, &inner)),
+            vec![(5., 5., 20., 10.), (5., 10., 10., 20.)]
+        );
+        assert!(intersect_spans(&outer, &[rect(50., 50., 1., 1.)]).is_empty());
+    }
+
+    #[test]
+    fn blurred_copies_blend_to_the_shape_color() {
+        for &alpha in &[0.25, 0.5, 0.9] {
+            for &count in &[1, 9, 25] {
+                let blended = 1. - (1. - sample_alpha(alpha, count)).powi(count as i32);
+                assert!((blended - alpha).abs() < 1e-4, "{} {}", alpha, count);
+            }
+        }
+        assert!((sample_alpha(1., 1) - 1.).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn blurred_copies_of_opaque_shapes_are_translucent() {
+        let samples = blur_samples(4.).len();
+        assert!(samples > 1);
+        // A single copy, where the copies only overlap at the shape's edges,
+        // is much fainter than the shape.
+        assert!(sample_alpha(1., samples) < 0.5);
+        let blended = 1. - (1. - sample_alpha(1., samples)).powi(samples as i32);
+        assert!(blended > 0.98);
+    }
+
+    fn points(polygon: &[Point<f32, Scaled>]) -> Vec<(f32, f32)> {
+        polygon
+            .iter()
+            .map(|point| {
+                (
+                    (point.x * 1000.).round() / 1000.,
+                    (point.y * 1000.).round() / 1000.,
+                )
+            })
+            .collect()
+    }
+
+    /// The corner of a line heading right that turns down, on the screen.
+    fn right_turn(style: &StrokeStyle) -> Vec<Point<f32, Scaled>> {
+        join_polygon(
+            Point::new(0., 10.),
+            Point::new(10., 10.),
+            Point::new(10., 20.),
+            2.,
+            style,
+        )
+    }
+
+    #[test]
+    fn joins_fill_the_outside_of_corners() {
+        let bevel = StrokeStyle::default().with_join(LineJoin::Bevel);
+        assert_eq!(
+            points(&right_turn(&bevel)),
+            vec![(10., 10.), (10., 8.), (12., 10.)]
+        );
+        assert_eq!(
+            points(&right_turn(&StrokeStyle::default())),
+            vec![(10., 10.), (10., 8.), (12., 8.), (12., 10.)]
+        );
+
+        let round = right_turn(&StrokeStyle::default().with_join(LineJoin::Round));
+        assert_eq!(points(&round[..2]), vec![(10., 10.), (10., 8.)]);
+        assert_eq!(points(&round[round.len() - 1..]), vec![(12., 10.)]);
+        for point in &round[1..] {
+            assert!(((point.x - 10.).hypot(point.y - 10.) - 2.).abs() < 1e-4);
+            assert!(point.x >= 10. - 1e-4 && point.y <= 10. + 1e-4);
+        }
+    }
+
+    #[test]
+    fn sharp_miters_are_beveled() {
+        // The miter of a right angle is sqrt(2) times the stroke width.
+        let style = StrokeStyle::default().with_miter_limit(1.4);
+        assert_eq!(right_turn(&style).len(), 3);
+        let style = StrokeStyle::default().with_miter_limit(1.5);
+        assert_eq!(right_turn(&style).len(), 4);
+    }
+
+    #[test]
+    fn straight_corners_arent_joined() {
+        assert!(join_polygon(
+            Point::new(0., 0.),
+            Point::new(5., 0.),
+            Point::new(10., 0.),
+            2.,
+            &StrokeStyle::default(),
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn round_caps_bulge_past_the_end() {
+        let end = Point::new(10., 10.);
+        let cap = arc(end, 2., (-1_f32).atan2(0.), PI).collect::<Vec<_>>();
+        let farthest = cap
+            .iter()
+            .map(|point| point.x)
+            .fold(f32::NEG_INFINITY, f32::max);
+        assert!((farthest - 12.).abs() < 1e-4);
+        assert!(cap.iter().all(|point| point.x >= 10. - 1e-4));
+    }
+}
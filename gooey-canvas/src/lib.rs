@@ -1,12 +1,19 @@
-use std::fmt::Debug;
+use std::{
+    any::Any,
+    fmt::Debug,
+    ops::Range,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
+#[cfg(feature = "frontend-browser")]
 use browser::BrowserRenderer;
 use gooey::{
     core::{
         assets::Image,
-        figures::{DisplayScale, Displayable, Point, Rect, Size},
+        figures::{DisplayScale, Displayable, ExtentsRect, Figure, Point, Rect, Rectlike, Size},
         styles::{Color, SystemTheme},
-        KeyedStorage, Pixels, Scaled, StyledWidget, Widget,
+        Callback, Context, KeyedStorage, Pixels, Scaled, StyledWidget, Widget,
     },
     frontends::rasterizer::ContentArea,
     renderer::{Renderer, StrokeOptions, TextOptions},
@@ -17,14 +24,78 @@ use gooey::{
 #[cfg(feature = "frontend-kludgine")]
 mod kludgine;
 
+use clip::ClipShape;
+use cursor::Cursors;
+use effects::Effects;
 #[cfg(feature = "frontend-kludgine")]
 use gooey::frontends::renderers::kludgine::Kludgine;
+use statistics::{Primitive, PrimitiveCounter};
 
+mod accessibility;
 #[cfg(feature = "frontend-browser")]
 mod browser;
+mod capture;
+#[cfg(feature = "charts")]
+pub mod charts;
+mod clip;
+mod cursor;
+mod drag;
+mod drag_tracker;
+mod effects;
+mod fonts;
+mod gestures;
+mod path;
+mod pointer;
+pub mod scene;
+mod smoothing;
+mod sprites;
+mod statistics;
+mod stroke;
+mod text_layout;
+mod texture;
+mod transform;
+mod viewport;
+
+pub use self::{
+    accessibility::{AccessibilityTree, AccessibleRegion, CanvasRole},
+    capture::CanvasImage,
+    cursor::CursorIcon,
+    drag::{DragEvent, DragEventKind, DragPayload, DroppedFile},
+    drag_tracker::{DragPhase, DragTracker, PointerDrag},
+    effects::Shadow,
+    fonts::{CanvasFont, FontFormat},
+    gestures::{
+        GestureEvent, GestureKind, GestureRecognizer, DOUBLE_TAP_INTERVAL, LONG_PRESS_DURATION,
+        TAP_SLOP,
+    },
+    path::Path,
+    pointer::{
+        EventStatus, Modifiers, PointerButton, PointerDevice, PointerEvent, PointerEventKind,
+    },
+    scene::Scene,
+    smoothing::ImageSmoothing,
+    sprites::{SpriteSheet, TileMap, TileMapCommand},
+    statistics::{CanvasStatistics, FrameStatistics, PrimitiveCounts},
+    stroke::{LineCap, LineJoin, StrokeStyle},
+    text_layout::{TextLayout, CARET_WIDTH},
+    texture::{Texture, TextureError},
+    transform::Transform,
+    viewport::{Viewport, ViewportCommand},
+};
 
 pub struct Canvas {
     renderable: Box<dyn Renderable>,
+    on_event: Option<Callback<Event>>,
+    statistics: CanvasStatistics,
+    show_statistics: bool,
+    accessibility: Option<AccessibilityTree>,
+    gestures: GestureRecognizer,
+    /// The cursor set with [`Command::SetCursor`].
+    cursor: Option<CursorIcon>,
+    /// The cursors requested while drawing the previous frame.
+    cursors: Cursors,
+    /// The fonts registered with this canvas.
+    fonts: Vec<CanvasFont>,
 }
 
 impl Debug for Canvas {
@@ -35,14 +106,243 @@ impl Debug for Canvas {
 
 impl Canvas {
     pub fn new<R: Renderable>(renderable: R) -> StyledWidget<Self> {
-        StyledWidget::from(Self {
+        StyledWidget::from(Self::from_renderable(renderable))
+    }
+
+    /// Returns a new canvas that invokes `on_event` with each [`Event`] that
+    /// `renderable` doesn't handle.
+    pub fn with_events<R: Renderable>(
+        renderable: R,
+        on_event: Callback<Event>,
+    ) -> StyledWidget<Self> {
+        let mut canvas = Self::from_renderable(renderable);
+        canvas.on_event = Some(on_event);
+        StyledWidget::from(canvas)
+    }
+
+    fn from_renderable<R: Renderable>(renderable: R) -> Self {
+        Self {
             renderable: Box::new(renderable),
-        })
+            on_event: None,
+            statistics: CanvasStatistics::default(),
+            show_statistics: false,
+            accessibility: None,
+            gestures: GestureRecognizer::default(),
+            cursor: None,
+            cursors: Cursors::default(),
+            fonts: Vec::new(),
+        }
+    }
+
+    /// Returns the description of this canvas for assistive technology. The
+    /// renderable's [`Renderable::accessibility()`] takes precedence over the
+    /// tree given to [`Builder::with_accessibility()`].
+    #[must_use]
+    pub fn accessibility(&self) -> Option<AccessibilityTree> {
+        self.renderable
+            .accessibility()
+            .or_else(|| self.accessibility.clone())
+    }
+
+    /// Returns the statistics collected while drawing this canvas.
+    #[must_use]
+    pub const fn statistics(&self) -> &CanvasStatistics {
+        &self.statistics
+    }
+
+    /// Renders a frame with `renderer`, recording statistics and drawing the
+    /// statistics overlay if it is enabled. `since_last_frame` is the time
+    /// since the previous frame was drawn.
+    pub(crate) fn render_frame(
+        &mut self,
+        renderer: CanvasRenderer,
+        content_area: &ContentArea,
+        since_last_frame: Option<Duration>,
+        now: impl Fn() -> Duration,
+    ) {
+        let primitives = renderer.primitive_counter();
+        let cursors = renderer.cursors.clone();
+        let dirty_region = renderer.dirty_region;
+        let overlay_renderer = self.show_statistics.then(|| CanvasRenderer {
+            backend: renderer.backend.clone(),
+            dirty_region: None,
+            transform: renderer.transform,
+            primitives: Arc::default(),
+            effects: Effects::default(),
+            stroke_style: StrokeStyle::default(),
+            image_smoothing: ImageSmoothing::default(),
+            pixel_snapping: false,
+            cursors: Arc::default(),
+            #[cfg(feature = "frontend-kludgine")]
+            clip_spans: None,
+        });
+
+        let render_start = now();
+        self.renderable.render(renderer, content_area);
+        let render_duration = now().saturating_sub(render_start);
+        self.statistics
+            .record_frame(render_duration, since_last_frame, primitives.counts());
+        let cursors = std::mem::take(&mut *cursors.lock().unwrap());
+        self.cursors = match dirty_region {
+            // The rest of the canvas still shows the previous frame, along
+            // with its cursors.
+            Some(dirty_region) => {
+                cursors.drawn_over(std::mem::take(&mut self.cursors), dirty_region)
+            }
+            None => cursors,
+        };
+
+        if let Some(overlay_renderer) = overlay_renderer {
+            statistics::render_overlay(&overlay_renderer, &self.statistics.snapshot());
+        }
+    }
+
+    /// Returns the cursor to show with the pointer at `location`, in the
+    /// coordinates of the frontend's renderer.
+    pub(crate) fn cursor_at(&self, location: Point<f32, Scaled>) -> CursorIcon {
+        self.cursors
+            .cursor_at(location)
+            .or(self.cursor)
+            .unwrap_or_default()
+    }
+
+    /// Gives the renderable a chance to handle `event`, and forwards it to the
+    /// event callback if it is ignored. Returns true if the canvas needs to be
+    /// redrawn.
+    pub(crate) fn handle_pointer(&mut self, event: PointerEvent, timestamp: Duration) -> bool {
+        let mut redraw = match self.renderable.pointer(&event) {
+            EventStatus::Processed => true,
+            EventStatus::Ignored => {
+                if let Some(on_event) = &self.on_event {
+                    on_event.invoke(Event::Pointer(event));
+                }
+                false
+            }
+        };
+        for gesture in self.gestures.pointer(&event, timestamp) {
+            redraw |= self.handle_gesture(gesture);
+        }
+        redraw
+    }
+
+    /// Delivers the long press gesture if it has been recognized by
+    /// `timestamp`. Returns true if the canvas needs to be redrawn.
+    pub(crate) fn poll_gestures(&mut self, timestamp: Duration) -> bool {
+        self.gestures
+            .poll(timestamp)
+            .map_or(false, |gesture| self.handle_gesture(gesture))
+    }
+
+    /// Gives the renderable a chance to handle `event`, and forwards it to the
+    /// event callback if it is ignored. Returns true if the canvas needs to be
+    /// redrawn.
+    pub(crate) fn handle_drag(&mut self, event: DragEvent) -> bool {
+        match self.renderable.drag(&event) {
+            EventStatus::Processed => true,
+            EventStatus::Ignored => {
+                if let Some(on_event) = &self.on_event {
+                    on_event.invoke(Event::Drag(event));
+                }
+                false
+            }
+        }
+    }
+
+    fn handle_gesture(&mut self, gesture: GestureEvent) -> bool {
+        match self.renderable.gesture(&gesture) {
+            EventStatus::Processed => true,
+            EventStatus::Ignored => {
+                if let Some(on_event) = &self.on_event {
+                    on_event.invoke(Event::Gesture(gesture));
+                }
+                false
+            }
+        }
     }
 }
 
+impl Drop for Canvas {
+    fn drop(&mut self) {
+        self.renderable.teardown();
+    }
+}
+
+/// Draws the contents of a [`Canvas`].
+///
+/// Only [`render()`](Self::render) is required. The remaining methods are
+/// lifecycle hooks that allow a renderable to own state that outlives a single
+/// frame, such as caches or animation timers.
 pub trait Renderable: Send + Sync + 'static {
+    /// Called once, when the [`Canvas`] is transmogrified by the frontend.
+    #[allow(unused_variables)]
+    fn initialize(&mut self, context: &Context<Canvas>) {}
+
+    /// Called before rendering whenever the size of the canvas changes,
+    /// including before the first frame.
+    #[allow(unused_variables)]
+    fn resized(&mut self, size: Size<f32, Scaled>) {}
+
+    /// Called before each frame is rendered. `elapsed` is the time since the
+    /// previous frame if that frame requested another one, otherwise it is
+    /// zero.
+    ///
+    /// Return true to have another frame drawn as soon as the frontend is
+    /// able to, which keeps animations running without any outside
+    /// [`Command::Refresh`].
+    #[allow(unused_variables)]
+    fn update(&mut self, elapsed: Duration) -> bool {
+        false
+    }
+
+    /// Receives a message sent with [`Command::Message`]. A frame is always
+    /// rendered after a message is received.
+    #[allow(unused_variables)]
+    fn receive(&mut self, message: Box<dyn Any + Send + Sync>) {}
+
+    /// Called when the pointer interacts with the canvas. Events that are
+    /// [ignored](EventStatus::Ignored) are sent to the canvas' event callback.
+    ///
+    /// A pointer pressed on the canvas is captured until it is released: its
+    /// moves and release are delivered even once it leaves the canvas. Use a
+    /// [`DragTracker`] to turn these events into drags. Kludgine only captures
+    /// presses that are [processed](EventStatus::Processed), or that are sent
+    /// to the canvas' event callback, and lets the widgets underneath handle
+    /// the rest. The ignored press is followed by
+    /// [`PointerEventKind::Cancel`].
+    #[allow(unused_variables)]
+    fn pointer(&mut self, event: &PointerEvent) -> EventStatus {
+        EventStatus::Ignored
+    }
+
+    /// Called when a gesture is recognized from the pointer input. Gestures
+    /// are recognized after the events that complete them are delivered to
+    /// [`pointer()`](Self::pointer). Events that are
+    /// [ignored](EventStatus::Ignored) are sent to the canvas' event callback.
+    #[allow(unused_variables)]
+    fn gesture(&mut self, event: &GestureEvent) -> EventStatus {
+        EventStatus::Ignored
+    }
+
+    /// Called when something is dragged over or dropped on the canvas. Events
+    /// that are [ignored](EventStatus::Ignored) are sent to the canvas' event
+    /// callback.
+    #[allow(unused_variables)]
+    fn drag(&mut self, event: &DragEvent) -> EventStatus {
+        EventStatus::Ignored
+    }
+
+    /// Draws the canvas.
     fn render(&mut self, renderer: CanvasRenderer, content_area: &ContentArea);
+
+    /// Returns a description of the canvas for assistive technology. Called
+    /// after each frame is rendered, so that regions can follow what was
+    /// drawn.
+    fn accessibility(&self) -> Option<AccessibilityTree> {
+        None
+    }
+
+    /// Called when the [`Canvas`] is dropped.
+    fn teardown(&mut self) {}
 }
 
 impl<F: FnMut(CanvasRenderer, &ContentArea) + Send + Sync + 'static> Renderable for F {
@@ -51,12 +351,66 @@ impl<F: FnMut(CanvasRenderer, &ContentArea) + Send + Sync + 'static> Renderable
     }
 }
 
-#[derive(Debug)]
-pub enum Event {}
+/// An event sent from a [`Canvas`] to its event callback.
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// A pointer event that the [`Renderable`] didn't handle.
+    Pointer(PointerEvent),
+    /// A gesture that the [`Renderable`] didn't handle.
+    Gesture(GestureEvent),
+    /// A drag-and-drop event that the [`Renderable`] didn't handle.
+    Drag(DragEvent),
+}
 
 #[derive(Debug)]
 pub enum Command {
     Refresh,
+    /// Redraws the region of the canvas, in canvas coordinates. Repeated
+    /// invalidations are accumulated until the next frame is drawn, and the
+    /// accumulated region is reported by [`CanvasRenderer::dirty_region()`].
+    Invalidate(Rect<f32, Scaled>),
+    /// Delivers a message to [`Renderable::receive()`] and redraws the canvas.
+    Message(Box<dyn Any + Send + Sync>),
+    /// Shows or hides an overlay displaying the canvas' [`FrameStatistics`].
+    ShowStatistics(bool),
+    /// Invokes the callback with the canvas' pixels once the next frame has
+    /// been drawn, including the statistics overlay if it is shown. The
+    /// callback receives `None` if the frontend is unable to read back the
+    /// canvas.
+    ///
+    /// Only the browser supports capturing. The rasterizer doesn't give
+    /// widgets access to the frame Kludgine renders, so the Kludgine frontend
+    /// always invokes the callback with `None`. Headless Kludgine apps can
+    /// capture the whole window with gooey's `App::headless()` instead.
+    Capture(Callback<Option<CanvasImage>>),
+    /// Sets the cursor shown over the canvas when the frame being shown
+    /// doesn't set one with [`CanvasRenderer::set_cursor()`] or
+    /// [`CanvasRenderer::set_cursor_region()`]. `None` restores the default
+    /// cursor.
+    ///
+    /// The browser sets the CSS `cursor` of the canvas element. The
+    /// rasterizer doesn't let widgets change the window's cursor, so Kludgine
+    /// ignores cursors.
+    SetCursor(Option<CursorIcon>),
+    /// Delivers a drag-and-drop event as if it came from the frontend. This
+    /// is how data from elsewhere in the application is dropped on a canvas.
+    ///
+    /// The browser reports drags onto the canvas itself. Kludgine doesn't
+    /// report files dragged onto the window: the rasterizer doesn't forward
+    /// winit's `HoveredFile` and `DroppedFile` events to widgets. Native apps
+    /// that need file drops must deliver them with this command, for example
+    /// using [`DroppedFile::from_path()`].
+    Drag(DragEvent),
+    /// Registers a font with the canvas, and redraws it once the font is
+    /// ready. See [`CanvasFont`] for how each frontend uses fonts.
+    RegisterFont(CanvasFont),
+}
+
+impl Command {
+    /// Returns a [`Command::Message`] containing `message`.
+    pub fn message<M: Any + Send + Sync>(message: M) -> Self {
+        Self::Message(Box::new(message))
+    }
 }
 
 impl Widget for Canvas {
@@ -80,88 +434,768 @@ impl AppExt for App {
     }
 }
 
+/// Renders to a [`Canvas`] using the current frontend's renderer.
+///
+/// `CanvasRenderer` used to be an enum of the frontend renderers. Code that
+/// matched on it should match on [`CanvasRenderer::backend()`] instead, which
+/// returns the same variants as a [`CanvasBackend`].
 #[derive(Debug)]
-pub enum CanvasRenderer {
+pub struct CanvasRenderer {
+    backend: CanvasBackend,
+    dirty_region: Option<Rect<f32, Scaled>>,
+    transform: Transform,
+    primitives: Arc<PrimitiveCounter>,
+    effects: Effects,
+    /// The stroke style, with lengths in the backend's coordinates.
+    stroke_style: StrokeStyle,
+    image_smoothing: ImageSmoothing,
+    pixel_snapping: bool,
+    /// The cursors requested while drawing this frame, shared by this renderer
+    /// and its clones.
+    cursors: Arc<Mutex<Cursors>>,
+    /// The rectangles that approximate a non-rectangular clip, in Kludgine's
+    /// coordinates.
+    #[cfg(feature = "frontend-kludgine")]
+    clip_spans: Option<Arc<Vec<Rect<f32, Scaled>>>>,
+}
+
+/// The frontend-specific renderer used by a [`CanvasRenderer`].
+#[derive(Debug, Clone)]
+pub enum CanvasBackend {
     #[cfg(feature = "frontend-kludgine")]
     RasterizerRenderer(Kludgine),
     #[cfg(feature = "frontend-browser")]
     BrowserRenderer(BrowserRenderer),
 }
 
-impl Renderer for CanvasRenderer {
-    fn theme(&self) -> SystemTheme {
-        match self {
+impl CanvasRenderer {
+    pub(crate) fn new(backend: CanvasBackend, dirty_region: Option<Rect<f32, Scaled>>) -> Self {
+        Self {
+            backend,
+            dirty_region,
+            transform: Transform::identity(),
+            primitives: Arc::default(),
+            effects: Effects::default(),
+            stroke_style: StrokeStyle::default(),
+            image_smoothing: ImageSmoothing::default(),
+            pixel_snapping: false,
+            cursors: Arc::default(),
+            #[cfg(feature = "frontend-kludgine")]
+            clip_spans: None,
+        }
+    }
+
+    /// Returns the frontend-specific renderer.
+    #[must_use]
+    pub const fn backend(&self) -> &CanvasBackend {
+        &self.backend
+    }
+
+    /// Returns the region of the canvas that needs to be repainted, in this
+    /// renderer's coordinates. When `None`, the entire canvas must be drawn.
+    ///
+    /// When `Some`, the pixels outside of this region still contain the
+    /// previous frame, and drawing is clipped to the region. Only frontends
+    /// that preserve the canvas between frames report a dirty region.
+    #[must_use]
+    pub fn dirty_region(&self) -> Option<Rect<f32, Scaled>> {
+        self.dirty_region
+            .map(|region| self.transform.inverse().transform_rect(region))
+    }
+
+    /// Returns the transform applied to all coordinates passed to this
+    /// renderer.
+    #[must_use]
+    pub const fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    /// Returns a renderer that applies `transform` before this renderer's
+    /// transform.
+    ///
+    /// Coordinates, stroke widths and text sizes are scaled. Images are
+    /// positioned by the transform, but are drawn at their original size, except
+    /// for [image regions](Self::draw_image_region) in the browser.
+    #[must_use]
+    pub fn transformed(&self, transform: &Transform) -> Self {
+        Self {
+            backend: self.backend.clone(),
+            dirty_region: self.dirty_region,
+            transform: transform.then(&self.transform),
+            primitives: self.primitives.clone(),
+            effects: self.effects,
+            stroke_style: self.stroke_style.clone(),
+            image_smoothing: self.image_smoothing,
+            pixel_snapping: self.pixel_snapping,
+            cursors: self.cursors.clone(),
             #[cfg(feature = "frontend-kludgine")]
-            Self::RasterizerRenderer(renderer) => renderer.theme(),
+            clip_spans: self.clip_spans.clone(),
+        }
+    }
+
+    /// Returns a renderer that only draws within `rect` with its corners
+    /// rounded by `radius`. Like [`Renderer::clip_to()`], the clip is
+    /// intersected with the current clip and the origin is unchanged.
+    ///
+    /// Kludgine only supports rectangular clipping, so it draws each primitive
+    /// once for each pixel row of the rounded corners that the primitive
+    /// overlaps, and edges aren't antialiased.
+    #[must_use]
+    pub fn clip_to_rounded_rect(&self, rect: Rect<f32, Scaled>, radius: f32) -> Self {
+        self.clip_to_shape(&ClipShape::rounded_rect(rect, radius))
+    }
+
+    /// Returns a renderer that only draws within the area enclosed by `path`,
+    /// using the even-odd rule. The path is treated as closed. Like
+    /// [`Renderer::clip_to()`], the clip is intersected with the current clip
+    /// and the origin is unchanged.
+    ///
+    /// Kludgine only supports rectangular clipping, so it draws each primitive
+    /// once for each pixel row of the path that has distinct spans and that
+    /// the primitive overlaps, and edges aren't antialiased. Text and images
+    /// loaded as assets aren't measured, so they are drawn for every row.
+    #[must_use]
+    pub fn clip_to_path(&self, path: &Path) -> Self {
+        self.clip_to_shape(&ClipShape::path(path))
+    }
+
+    fn clip_to_shape(&self, shape: &ClipShape) -> Self {
+        let shape = shape.transformed(&self.transform);
+        let clipped = self.clip_to_backend_rect(shape.bounds());
+        match &clipped.backend {
+            #[cfg(feature = "frontend-kludgine")]
+            CanvasBackend::RasterizerRenderer(renderer) => {
+                let spans = kludgine::clip_spans(&shape, renderer.clip_bounds(), &renderer.scale());
+                let spans = match &clipped.clip_spans {
+                    Some(existing) => kludgine::intersect_spans(existing, &spans),
+                    None => spans,
+                };
+                Self {
+                    clip_spans: Some(Arc::new(spans)),
+                    ..clipped
+                }
+            }
             #[cfg(feature = "frontend-browser")]
-            Self::BrowserRenderer(renderer) => renderer.theme(),
+            CanvasBackend::BrowserRenderer(renderer) => Self {
+                backend: CanvasBackend::BrowserRenderer(renderer.clip_to_shape(shape)),
+                ..clipped
+            },
         }
     }
 
-    fn size(&self) -> Size<f32, Scaled> {
-        match self {
+    fn clip_to_backend_rect(&self, bounds: Rect<f32, Scaled>) -> Self {
+        let backend = match &self.backend {
             #[cfg(feature = "frontend-kludgine")]
-            Self::RasterizerRenderer(renderer) => renderer.size(),
+            CanvasBackend::RasterizerRenderer(renderer) => {
+                CanvasBackend::RasterizerRenderer(renderer.clip_to(bounds))
+            }
             #[cfg(feature = "frontend-browser")]
-            Self::BrowserRenderer(renderer) => renderer.size(),
+            CanvasBackend::BrowserRenderer(renderer) => {
+                CanvasBackend::BrowserRenderer(renderer.clip_to(bounds))
+            }
+        };
+        Self {
+            backend,
+            dirty_region: self.dirty_region,
+            transform: self.transform,
+            primitives: self.primitives.clone(),
+            effects: self.effects,
+            stroke_style: self.stroke_style.clone(),
+            image_smoothing: self.image_smoothing,
+            pixel_snapping: self.pixel_snapping,
+            cursors: self.cursors.clone(),
+            #[cfg(feature = "frontend-kludgine")]
+            clip_spans: self
+                .clip_spans
+                .as_ref()
+                .map(|spans| Arc::new(kludgine::intersect_spans(spans, &[bounds]))),
         }
     }
 
-    fn clip_to(&self, bounds: Rect<f32, Scaled>) -> Self {
-        match self {
+    /// Calls `draw` with `renderer`, or once for each rectangle approximating
+    /// a non-rectangular clip. `bounds` is the area `draw` covers before
+    /// effects are applied, in Kludgine's coordinates. When it is known,
+    /// rectangles of the clip that it can't reach are skipped.
+    #[cfg(feature = "frontend-kludgine")]
+    fn draw_kludgine<F: Fn(&Kludgine)>(
+        &self,
+        renderer: &Kludgine,
+        bounds: Option<Rect<f32, Scaled>>,
+        draw: F,
+    ) {
+        match &self.clip_spans {
+            Some(spans) => {
+                let bounds = bounds.map(|bounds| {
+                    kludgine::outset(bounds, kludgine::effects_extent(&self.effects))
+                });
+                for span in spans.iter() {
+                    if bounds.map_or(true, |bounds| kludgine::intersect(*span, bounds).is_some()) {
+                        draw(&renderer.clip_to(*span));
+                    }
+                }
+            }
+            None => draw(renderer),
+        }
+    }
+
+    /// Returns a renderer that draws `shadow` beneath filled rectangles, text
+    /// and images. The shadow's offset and blur radius are in this renderer's
+    /// coordinates.
+    ///
+    /// Kludgine approximates the blur by drawing several translucent copies of
+    /// each shape, and only draws shadows for [`Texture`]s, not for images
+    /// loaded as assets.
+    #[must_use]
+    pub fn with_shadow(&self, shadow: Shadow) -> Self {
+        self.with_effects(self.effects.with_shadow(Some(shadow), &self.transform))
+    }
+
+    /// Returns a renderer that doesn't draw shadows.
+    #[must_use]
+    pub fn without_shadow(&self) -> Self {
+        self.with_effects(self.effects.with_shadow(None, &self.transform))
+    }
+
+    /// Returns a renderer that applies a Gaussian blur of `radius`, in this
+    /// renderer's coordinates, to filled rectangles, text and images. A radius
+    /// of 0 disables blurring.
+    ///
+    /// Kludgine approximates the blur by drawing several translucent copies of
+    /// each shape, and only blurs [`Texture`]s, not images loaded as assets.
+    #[must_use]
+    pub fn with_blur(&self, radius: f32) -> Self {
+        self.with_effects(self.effects.with_blur(radius, &self.transform))
+    }
+
+    fn with_effects(&self, effects: Effects) -> Self {
+        let mut renderer = self.transformed(&Transform::identity());
+        renderer.effects = effects;
+        #[cfg(feature = "frontend-browser")]
+        if let CanvasBackend::BrowserRenderer(backend) = &mut renderer.backend {
+            backend.set_effects(effects);
+        }
+        renderer
+    }
+
+    /// Returns a renderer that strokes lines, rectangles and paths with
+    /// `style`. Dash lengths and offsets are in this renderer's coordinates.
+    ///
+    /// Kludgine fills joins and round caps one pixel row at a time, so their
+    /// edges aren't antialiased.
+    #[must_use]
+    pub fn with_stroke_style(&self, style: StrokeStyle) -> Self {
+        let mut renderer = self.transformed(&Transform::identity());
+        renderer.stroke_style = style.scaled(self.transform.scale);
+        #[cfg(feature = "frontend-browser")]
+        if let CanvasBackend::BrowserRenderer(backend) = &mut renderer.backend {
+            backend.set_stroke_style(renderer.stroke_style.clone());
+        }
+        renderer
+    }
+
+    /// Returns a renderer that resamples images with `smoothing`. Disable
+    /// smoothing to keep pixel-art sprites crisp.
+    ///
+    /// The rasterizer doesn't let widgets choose the sampler Kludgine draws
+    /// images with, so Kludgine samples every image the same way and logs a
+    /// warning when another level is requested. It only honors
+    /// [`ImageSmoothing::Disabled`] by drawing images on whole device pixels.
+    #[must_use]
+    pub fn with_image_smoothing(&self, smoothing: ImageSmoothing) -> Self {
+        let mut renderer = self.transformed(&Transform::identity());
+        renderer.image_smoothing = smoothing;
+        #[cfg(feature = "frontend-kludgine")]
+        if let CanvasBackend::RasterizerRenderer(_) = &renderer.backend {
+            kludgine::warn_image_smoothing(smoothing);
+        }
+        #[cfg(feature = "frontend-browser")]
+        if let CanvasBackend::BrowserRenderer(backend) = &mut renderer.backend {
+            backend.set_image_smoothing(smoothing);
+        }
+        renderer
+    }
+
+    /// Returns a renderer that moves lines, rectangles and paths onto device
+    /// pixels when `enabled`, so that thin strokes and the edges of filled
+    /// rectangles are drawn crisply instead of being blended across two
+    /// pixels. This assumes the canvas itself starts on a whole device pixel.
+    #[must_use]
+    pub fn with_pixel_snapping(&self, enabled: bool) -> Self {
+        let mut renderer = self.transformed(&Transform::identity());
+        renderer.pixel_snapping = enabled;
+        renderer
+    }
+
+    /// Shows `cursor` while the pointer is over the canvas and outside of the
+    /// regions set with [`set_cursor_region()`](Self::set_cursor_region).
+    /// Cursors only last for the frame they are set in, and
+    /// [`Command::SetCursor`] is shown when a frame sets none. A frame that
+    /// only redraws its [`dirty_region()`](Self::dirty_region) keeps the
+    /// previous frame's cursors outside of that region.
+    ///
+    /// Only the browser shows cursors. The rasterizer doesn't let widgets
+    /// change the window's cursor, so Kludgine always shows its default.
+    pub fn set_cursor(&self, cursor: CursorIcon) {
+        self.cursors.lock().unwrap().frame = Some(cursor);
+    }
+
+    /// Shows `cursor` while the pointer is within `region`. Where regions
+    /// overlap, the one set last is used.
+    pub fn set_cursor_region(
+        &self,
+        region: &impl Displayable<f32, Pixels = Rect<f32, Pixels>>,
+        cursor: CursorIcon,
+    ) {
+        let region = self.canvas_rect(region);
+        self.cursors.lock().unwrap().regions.push((region, cursor));
+    }
+
+    /// Strokes `path`. Its corners are joined and its dashes continue from
+    /// one segment to the next, according to the
+    /// [stroke style](Self::with_stroke_style).
+    pub fn stroke_path(&self, path: &Path, options: &StrokeOptions) {
+        for _ in path.segments() {
+            self.primitives.increment(Primitive::Line);
+        }
+        let closed = path.is_closed() && path.points().len() > 2;
+        let mut points = path
+            .points()
+            .iter()
+            .map(|point| self.stroke_point(self.canvas_point(*point), options))
+            .collect::<Vec<_>>();
+        if closed {
+            points.push(points[0]);
+        }
+        self.stroke_polyline(&points, closed, &self.canvas_stroke(options));
+    }
+
+    /// Strokes the lines joining `points`, in the backend's coordinates. When
+    /// `closed` is true, the last point must repeat the first.
+    fn stroke_polyline(
+        &self,
+        points: &[Point<f32, Scaled>],
+        closed: bool,
+        options: &StrokeOptions,
+    ) {
+        match &self.backend {
             #[cfg(feature = "frontend-kludgine")]
-            Self::RasterizerRenderer(renderer) => {
-                Self::RasterizerRenderer(renderer.clip_to(bounds))
+            CanvasBackend::RasterizerRenderer(renderer) => {
+                let bounds = Path::from_points(points.iter().copied())
+                    .bounds()
+                    .map(|bounds| {
+                        kludgine::outset(
+                            bounds,
+                            kludgine::stroke_extent(options, &self.stroke_style),
+                        )
+                    });
+                self.draw_kludgine(renderer, bounds, |renderer| {
+                    kludgine::stroke_polyline(renderer, points, closed, options, &self.stroke_style)
+                })
             }
             #[cfg(feature = "frontend-browser")]
-            Self::BrowserRenderer(renderer) => Self::BrowserRenderer(renderer.clip_to(bounds)),
+            CanvasBackend::BrowserRenderer(renderer) => {
+                renderer.stroke_path(points, closed, options)
+            }
         }
     }
 
-    fn clip_bounds(&self) -> Rect<f32, Scaled> {
-        match self {
+    /// Draws `text` with its baseline starting at `baseline_origin`, rotated
+    /// clockwise by `angle` radians around that point.
+    ///
+    /// Kludgine can't rotate glyphs, so it places each character upright along
+    /// the rotated baseline, spaced by its measured advance.
+    pub fn render_text_rotated(
+        &self,
+        text: &str,
+        baseline_origin: impl Displayable<f32, Pixels = Point<f32, Pixels>>,
+        angle: f32,
+        options: &TextOptions,
+    ) {
+        let origin = self.canvas_point(baseline_origin);
+        let canvas_options = &self.canvas_text(options);
+        match &self.backend {
             #[cfg(feature = "frontend-kludgine")]
-            Self::RasterizerRenderer(renderer) => renderer.clip_bounds(),
+            CanvasBackend::RasterizerRenderer(_) => {
+                let (sin, cos) = angle.sin_cos();
+                for glyph in text_layout::glyphs(self, text, options) {
+                    self.primitives.increment(Primitive::Text);
+                    let offset = glyph.offset * self.transform.scale;
+                    self.draw_text(
+                        glyph.text,
+                        Point::new(origin.x + cos * offset, origin.y + sin * offset),
+                        canvas_options,
+                    );
+                }
+            }
             #[cfg(feature = "frontend-browser")]
-            Self::BrowserRenderer(renderer) => renderer.clip_bounds(),
+            CanvasBackend::BrowserRenderer(renderer) => {
+                self.primitives.increment(Primitive::Text);
+                renderer.render_glyphs(&[(text, origin, angle)], canvas_options)
+            }
         }
     }
 
-    fn scale(&self) -> DisplayScale<f32> {
-        match self {
+    /// Draws `text` along `path`, starting `offset` from the start of the
+    /// path. Each character is centered on the path and rotated to follow
+    /// it. Characters beyond the end of the path aren't drawn.
+    ///
+    /// Kludgine can't rotate glyphs, so it draws each character upright.
+    pub fn render_text_on_path(&self, text: &str, path: &Path, offset: f32, options: &TextOptions) {
+        let glyphs = text_layout::glyphs(self, text, options)
+            .into_iter()
+            .filter_map(|glyph| {
+                let (center, angle) = path.point_at(offset + glyph.offset + glyph.advance / 2.)?;
+                let (sin, cos) = angle.sin_cos();
+                let half = glyph.advance / 2.;
+                let origin = self.canvas_point(Point::<f32, Scaled>::new(
+                    center.x - cos * half,
+                    center.y - sin * half,
+                ));
+                Some((glyph.text, origin, angle))
+            })
+            .collect::<Vec<_>>();
+        for _ in &glyphs {
+            self.primitives.increment(Primitive::Text);
+        }
+        let options = &self.canvas_text(options);
+        match &self.backend {
             #[cfg(feature = "frontend-kludgine")]
-            Self::RasterizerRenderer(renderer) => renderer.scale(),
+            CanvasBackend::RasterizerRenderer(_) => {
+                for (text, origin, _) in glyphs {
+                    self.draw_text(text, origin, options);
+                }
+            }
             #[cfg(feature = "frontend-browser")]
-            Self::BrowserRenderer(renderer) => renderer.scale(),
+            CanvasBackend::BrowserRenderer(renderer) => renderer.render_glyphs(&glyphs, options),
         }
     }
 
-    fn render_text(
+    /// Measures `text` as it would be drawn by
+    /// [`render_text()`](Renderer::render_text) at `baseline_origin`, for
+    /// hit-testing, caret placement and selection. The text is laid out as a
+    /// single line.
+    #[must_use]
+    pub fn layout_text(
         &self,
         text: &str,
         baseline_origin: impl Displayable<f32, Pixels = Point<f32, Pixels>>,
         options: &TextOptions,
-    ) {
-        match self {
+    ) -> TextLayout {
+        let scale = self.backend_scale();
+        TextLayout::new(
+            self,
+            text,
+            baseline_origin.to_pixels(&scale).to_scaled(&scale),
+            options,
+            self.font_extents(text, options),
+        )
+    }
+
+    /// Returns the ascent and descent of the font `options` draws `text`
+    /// with, which unlike the bounds of the text itself don't depend on its
+    /// characters.
+    fn font_extents(&self, text: &str, options: &TextOptions) -> (f32, f32) {
+        let measured = || {
+            let metrics = self.measure_text(text, options);
+            (metrics.ascent.get(), metrics.descent.get())
+        };
+        match &self.backend {
+            // Kludgine measures text with the font's ascent and descent.
             #[cfg(feature = "frontend-kludgine")]
-            Self::RasterizerRenderer(renderer) => {
+            CanvasBackend::RasterizerRenderer(_) => measured(),
+            #[cfg(feature = "frontend-browser")]
+            CanvasBackend::BrowserRenderer(renderer) => {
+                renderer.font_extents(options).unwrap_or_else(measured)
+            }
+        }
+    }
+
+    /// Fills the area behind the characters of `layout` in `range` with
+    /// `color`. Draw selections before the text so that the text remains
+    /// visible.
+    pub fn draw_selection(&self, layout: &TextLayout, range: Range<usize>, color: Color) {
+        self.fill_rect(&layout.selection_rect(range), color);
+    }
+
+    /// Draws a caret before the character of `layout` at `index`.
+    pub fn draw_caret(&self, layout: &TextLayout, index: usize, color: Color) {
+        self.fill_rect(&layout.caret_rect(index), color);
+    }
+
+    /// Draws `text` at `baseline_origin` with `options`, both in the backend's
+    /// coordinates.
+    fn draw_text(&self, text: &str, baseline_origin: Point<f32, Scaled>, options: &TextOptions) {
+        match &self.backend {
+            #[cfg(feature = "frontend-kludgine")]
+            CanvasBackend::RasterizerRenderer(renderer) => {
+                self.draw_kludgine(renderer, None, |renderer| {
+                    kludgine::draw_with_effects(&self.effects, options.color, |offset, color| {
+                        renderer.render_text(
+                            text,
+                            Point::<f32, Scaled>::new(
+                                baseline_origin.x + offset.x,
+                                baseline_origin.y + offset.y,
+                            ),
+                            &TextOptions {
+                                color,
+                                ..options.clone()
+                            },
+                        );
+                    });
+                })
+            }
+            #[cfg(feature = "frontend-browser")]
+            CanvasBackend::BrowserRenderer(renderer) => {
                 renderer.render_text(text, baseline_origin, options)
             }
+        }
+    }
+
+    /// Draws the `source` region of `image`, in image pixels, with its top-left
+    /// corner at `location`.
+    ///
+    /// In the browser, the region is scaled by this renderer's
+    /// [transform](Self::transform). Kludgine can only draw images at their
+    /// natural size.
+    pub fn draw_image_region(
+        &self,
+        image: &Image,
+        source: Rect<u32, Pixels>,
+        location: impl Displayable<f32, Pixels = Point<f32, Pixels>>,
+    ) {
+        self.draw_image_regions(image, std::iter::once((source, location)));
+    }
+
+    /// Draws several regions of `image`. Each item is a `source` region in
+    /// image pixels and the `location` of its top-left corner. Regions are
+    /// scaled like [`draw_image_region()`](Self::draw_image_region).
+    ///
+    /// This is more efficient than calling
+    /// [`draw_image_region()`](Self::draw_image_region) repeatedly in the
+    /// browser, which looks up the source element and prepares the canvas
+    /// once. Kludgine draws each region separately.
+    pub fn draw_image_regions<P, I>(&self, image: &Image, regions: I)
+    where
+        P: Displayable<f32, Pixels = Point<f32, Pixels>>,
+        I: IntoIterator<Item = (Rect<u32, Pixels>, P)>,
+    {
+        let regions = regions
+            .into_iter()
+            .map(|(source, location)| {
+                self.primitives.increment(Primitive::Image);
+                (source, self.image_point(location))
+            })
+            .collect::<Vec<_>>();
+        match &self.backend {
+            #[cfg(feature = "frontend-kludgine")]
+            CanvasBackend::RasterizerRenderer(renderer) => {
+                let scale = renderer.scale();
+                let bounds = Path::from_points(regions.iter().flat_map(|(source, location)| {
+                    let size = source.cast::<f32>().to_scaled(&scale).as_sized().size;
+                    [
+                        *location,
+                        Point::new(location.x + size.width, location.y + size.height),
+                    ]
+                }))
+                .bounds();
+                self.draw_kludgine(renderer, bounds, |renderer| {
+                    kludgine::draw_image_regions(renderer, image, regions.iter().copied())
+                })
+            }
+            #[cfg(feature = "frontend-browser")]
+            CanvasBackend::BrowserRenderer(renderer) => {
+                renderer.draw_image_regions(image, regions.into_iter(), self.transform.scale)
+            }
+        }
+    }
+
+    /// Draws `texture` at its natural size with its top-left corner at
+    /// `location`.
+    ///
+    /// The rasterizer only lets widgets draw images loaded as assets, so
+    /// Kludgine never uploads a texture's pixels to the GPU. Instead it fills
+    /// a rectangle for each block of same-colored pixels, which is fast for
+    /// images with large areas of flat color, like heatmaps, but draws about
+    /// one rectangle per pixel for photographic images.
+    pub fn draw_texture(
+        &self,
+        texture: &Texture,
+        location: impl Displayable<f32, Pixels = Point<f32, Pixels>>,
+    ) {
+        self.primitives.increment(Primitive::Image);
+        let location = self.image_point(location);
+        match &self.backend {
+            #[cfg(feature = "frontend-kludgine")]
+            CanvasBackend::RasterizerRenderer(renderer) => {
+                let size = texture.size().cast::<f32>().to_scaled(&renderer.scale());
+                let bounds = Rect::from(ExtentsRect::new(
+                    location,
+                    Point::new(location.x + size.width, location.y + size.height),
+                ));
+                self.draw_kludgine(renderer, Some(bounds), |renderer| {
+                    kludgine::draw_texture(renderer, texture, location, &self.effects)
+                })
+            }
+            #[cfg(feature = "frontend-browser")]
+            CanvasBackend::BrowserRenderer(renderer) => renderer.draw_texture(texture, location),
+        }
+    }
+
+    /// Returns the counter shared by this renderer and its clones.
+    pub(crate) fn primitive_counter(&self) -> Arc<PrimitiveCounter> {
+        self.primitives.clone()
+    }
+
+    fn canvas_point(
+        &self,
+        point: impl Displayable<f32, Pixels = Point<f32, Pixels>>,
+    ) -> Point<f32, Scaled> {
+        let scale = self.backend_scale();
+        self.transform
+            .transform_point(point.to_pixels(&scale).to_scaled(&scale))
+    }
+
+    fn canvas_rect(
+        &self,
+        rect: &impl Displayable<f32, Pixels = Rect<f32, Pixels>>,
+    ) -> Rect<f32, Scaled> {
+        let scale = self.backend_scale();
+        self.transform
+            .transform_rect(rect.to_pixels(&scale).to_scaled(&scale))
+    }
+
+    /// Returns where an image drawn at `location` is placed, in the backend's
+    /// coordinates.
+    fn image_point(
+        &self,
+        location: impl Displayable<f32, Pixels = Point<f32, Pixels>>,
+    ) -> Point<f32, Scaled> {
+        let location = self.canvas_point(location);
+        if self.image_smoothing == ImageSmoothing::Disabled {
+            smoothing::snap_point(location, &self.backend_scale())
+        } else {
+            location
+        }
+    }
+
+    /// Returns true if the backend scales image regions by the transform.
+    pub(crate) const fn scales_images(&self) -> bool {
+        match &self.backend {
+            #[cfg(feature = "frontend-kludgine")]
+            CanvasBackend::RasterizerRenderer(_) => false,
+            #[cfg(feature = "frontend-browser")]
+            CanvasBackend::BrowserRenderer(_) => true,
+        }
+    }
+
+    /// Returns `point`, in the backend's coordinates, snapped for a stroke
+    /// with `options` if pixel snapping is enabled.
+    fn stroke_point(
+        &self,
+        point: Point<f32, Scaled>,
+        options: &StrokeOptions,
+    ) -> Point<f32, Scaled> {
+        if self.pixel_snapping {
+            smoothing::snap_stroke_point(
+                point,
+                options.line_width.get() * self.transform.scale,
+                &self.backend_scale(),
+            )
+        } else {
+            point
+        }
+    }
+
+    fn canvas_stroke(&self, options: &StrokeOptions) -> StrokeOptions {
+        StrokeOptions {
+            line_width: Figure::new(options.line_width.get() * self.transform.scale),
+            ..options.clone()
+        }
+    }
+
+    fn canvas_text(&self, options: &TextOptions) -> TextOptions {
+        TextOptions {
+            text_size: Figure::new(options.text_size.get() * self.transform.scale),
+            ..options.clone()
+        }
+    }
+
+    fn backend_scale(&self) -> DisplayScale<f32> {
+        match &self.backend {
+            #[cfg(feature = "frontend-kludgine")]
+            CanvasBackend::RasterizerRenderer(renderer) => renderer.scale(),
             #[cfg(feature = "frontend-browser")]
-            Self::BrowserRenderer(renderer) => renderer.render_text(text, baseline_origin, options),
+            CanvasBackend::BrowserRenderer(renderer) => renderer.scale(),
+        }
+    }
+}
+
+impl Renderer for CanvasRenderer {
+    fn theme(&self) -> SystemTheme {
+        match &self.backend {
+            #[cfg(feature = "frontend-kludgine")]
+            CanvasBackend::RasterizerRenderer(renderer) => renderer.theme(),
+            #[cfg(feature = "frontend-browser")]
+            CanvasBackend::BrowserRenderer(renderer) => renderer.theme(),
         }
     }
 
+    fn size(&self) -> Size<f32, Scaled> {
+        let size = match &self.backend {
+            #[cfg(feature = "frontend-kludgine")]
+            CanvasBackend::RasterizerRenderer(renderer) => renderer.size(),
+            #[cfg(feature = "frontend-browser")]
+            CanvasBackend::BrowserRenderer(renderer) => renderer.size(),
+        };
+        self.transform.inverse().transform_size(size)
+    }
+
+    /// Returns a renderer that only draws within `bounds`, in this renderer's
+    /// coordinates. The bounds are intersected with the current clip, and
+    /// the origin is unchanged: the returned renderer draws at the same
+    /// coordinates as this one.
+    fn clip_to(&self, bounds: Rect<f32, Scaled>) -> Self {
+        self.clip_to_backend_rect(self.transform.transform_rect(bounds))
+    }
+
+    fn clip_bounds(&self) -> Rect<f32, Scaled> {
+        let bounds = match &self.backend {
+            #[cfg(feature = "frontend-kludgine")]
+            CanvasBackend::RasterizerRenderer(renderer) => renderer.clip_bounds(),
+            #[cfg(feature = "frontend-browser")]
+            CanvasBackend::BrowserRenderer(renderer) => renderer.clip_bounds(),
+        };
+        self.transform.inverse().transform_rect(bounds)
+    }
+
+    fn scale(&self) -> DisplayScale<f32> {
+        self.backend_scale()
+    }
+
+    fn render_text(
+        &self,
+        text: &str,
+        baseline_origin: impl Displayable<f32, Pixels = Point<f32, Pixels>>,
+        options: &TextOptions,
+    ) {
+        self.primitives.increment(Primitive::Text);
+        self.draw_text(
+            text,
+            self.canvas_point(baseline_origin),
+            &self.canvas_text(options),
+        );
+    }
+
     fn measure_text(
         &self,
         text: &str,
         options: &TextOptions,
     ) -> gooey::renderer::TextMetrics<Scaled> {
-        match self {
+        match &self.backend {
             #[cfg(feature = "frontend-kludgine")]
-            Self::RasterizerRenderer(renderer) => renderer.measure_text(text, options),
+            CanvasBackend::RasterizerRenderer(renderer) => renderer.measure_text(text, options),
             #[cfg(feature = "frontend-browser")]
-            Self::BrowserRenderer(renderer) => renderer.measure_text(text, options),
+            CanvasBackend::BrowserRenderer(renderer) => renderer.measure_text(text, options),
         }
     }
 
@@ -170,20 +1204,61 @@ impl Renderer for CanvasRenderer {
         rect: &impl Displayable<f32, Pixels = Rect<f32, Pixels>>,
         options: &StrokeOptions,
     ) {
-        match self {
+        self.primitives.increment(Primitive::StrokedRect);
+        let options = &self.canvas_stroke(options);
+        let mut rect = self.canvas_rect(rect);
+        if self.pixel_snapping {
+            rect =
+                smoothing::snap_stroke_rect(rect, options.line_width.get(), &self.backend_scale());
+        }
+        let rect = &rect;
+        match &self.backend {
             #[cfg(feature = "frontend-kludgine")]
-            Self::RasterizerRenderer(renderer) => renderer.stroke_rect(rect, options),
+            CanvasBackend::RasterizerRenderer(renderer) => {
+                if self.stroke_style == StrokeStyle::default() {
+                    let bounds = kludgine::outset(
+                        *rect,
+                        kludgine::stroke_extent(options, &self.stroke_style),
+                    );
+                    self.draw_kludgine(renderer, Some(bounds), |renderer| {
+                        renderer.stroke_rect(rect, options)
+                    })
+                } else {
+                    let rect = rect.as_extents();
+                    let corners = [
+                        rect.origin,
+                        Point::new(rect.extent.x, rect.origin.y),
+                        rect.extent,
+                        Point::new(rect.origin.x, rect.extent.y),
+                        rect.origin,
+                    ];
+                    self.stroke_polyline(&corners, true, options);
+                }
+            }
             #[cfg(feature = "frontend-browser")]
-            Self::BrowserRenderer(renderer) => renderer.stroke_rect(rect, options),
+            CanvasBackend::BrowserRenderer(renderer) => renderer.stroke_rect(rect, options),
         }
     }
 
     fn fill_rect(&self, rect: &impl Displayable<f32, Pixels = Rect<f32, Pixels>>, color: Color) {
-        match self {
+        self.primitives.increment(Primitive::FilledRect);
+        let mut rect = self.canvas_rect(rect);
+        if self.pixel_snapping {
+            rect = smoothing::snap_rect(rect, &self.backend_scale());
+        }
+        let rect = &rect;
+        match &self.backend {
             #[cfg(feature = "frontend-kludgine")]
-            Self::RasterizerRenderer(renderer) => renderer.fill_rect(rect, color),
+            CanvasBackend::RasterizerRenderer(renderer) => {
+                self.draw_kludgine(renderer, Some(*rect), |renderer| {
+                    kludgine::draw_with_effects(&self.effects, color, |offset, color| {
+                        renderer
+                            .fill_rect(&Transform::new(1., offset).transform_rect(*rect), color);
+                    });
+                })
+            }
             #[cfg(feature = "frontend-browser")]
-            Self::BrowserRenderer(renderer) => renderer.fill_rect(rect, color),
+            CanvasBackend::BrowserRenderer(renderer) => renderer.fill_rect(rect, color),
         }
     }
 
@@ -193,11 +1268,35 @@ impl Renderer for CanvasRenderer {
         point_b: P,
         options: &StrokeOptions,
     ) {
-        match self {
+        self.primitives.increment(Primitive::Line);
+        let (point_a, point_b) = (
+            self.stroke_point(self.canvas_point(point_a), options),
+            self.stroke_point(self.canvas_point(point_b), options),
+        );
+        let options = &self.canvas_stroke(options);
+        match &self.backend {
             #[cfg(feature = "frontend-kludgine")]
-            Self::RasterizerRenderer(renderer) => renderer.stroke_line(point_a, point_b, options),
+            CanvasBackend::RasterizerRenderer(renderer) => {
+                if self.stroke_style == StrokeStyle::default() {
+                    let bounds = Path::from_points([point_a, point_b].iter().copied())
+                        .bounds()
+                        .map(|bounds| {
+                            kludgine::outset(
+                                bounds,
+                                kludgine::stroke_extent(options, &self.stroke_style),
+                            )
+                        });
+                    self.draw_kludgine(renderer, bounds, |renderer| {
+                        renderer.stroke_line(point_a, point_b, options)
+                    })
+                } else {
+                    self.stroke_polyline(&[point_a, point_b], false, options);
+                }
+            }
             #[cfg(feature = "frontend-browser")]
-            Self::BrowserRenderer(renderer) => renderer.stroke_line(point_a, point_b, options),
+            CanvasBackend::BrowserRenderer(renderer) => {
+                renderer.stroke_line(point_a, point_b, options)
+            }
         }
     }
 
@@ -206,11 +1305,17 @@ impl Renderer for CanvasRenderer {
         image: &Image,
         location: impl Displayable<f32, Pixels = Point<f32, Pixels>>,
     ) {
-        match self {
+        self.primitives.increment(Primitive::Image);
+        let location = self.image_point(location);
+        match &self.backend {
             #[cfg(feature = "frontend-kludgine")]
-            Self::RasterizerRenderer(renderer) => renderer.draw_image(image, location),
+            CanvasBackend::RasterizerRenderer(renderer) => {
+                self.draw_kludgine(renderer, None, |renderer| {
+                    renderer.draw_image(image, location)
+                })
+            }
             #[cfg(feature = "frontend-browser")]
-            Self::BrowserRenderer(renderer) => renderer.draw_image(image, location),
+            CanvasBackend::BrowserRenderer(renderer) => renderer.draw_image(image, location),
         }
     }
 }
@@ -223,18 +1328,64 @@ impl<B: Behavior<Widgets = ()>> Content<B> for Canvas {
 pub struct Builder<S: KeyedStorage<()>> {
     storage: S,
     canvas: Option<Canvas>,
+    on_event: Option<Callback<Event>>,
+    statistics: Option<CanvasStatistics>,
+    show_statistics: bool,
+    accessibility: Option<AccessibilityTree>,
+    fonts: Vec<CanvasFont>,
 }
 
 impl<S: KeyedStorage<()>> Builder<S> {
     pub fn on_render<R: Renderable>(mut self, renderable: R) -> Self {
-        self.canvas = Some(Canvas {
-            renderable: Box::new(renderable),
-        });
+        self.canvas = Some(Canvas::from_renderable(renderable));
+        self
+    }
+
+    /// Invokes `on_event` with each [`Event`] that the renderable doesn't
+    /// handle.
+    pub fn on_event(mut self, on_event: Callback<Event>) -> Self {
+        self.on_event = Some(on_event);
+        self
+    }
+
+    /// Records statistics into `statistics`, allowing them to be read after
+    /// the canvas is built.
+    pub fn with_statistics(mut self, statistics: CanvasStatistics) -> Self {
+        self.statistics = Some(statistics);
+        self
+    }
+
+    /// Shows an overlay displaying the canvas' [`FrameStatistics`]. The
+    /// overlay can also be toggled with [`Command::ShowStatistics`].
+    pub fn show_statistics(mut self, show: bool) -> Self {
+        self.show_statistics = show;
+        self
+    }
+
+    /// Describes the canvas to assistive technology. Renderables that
+    /// implement [`Renderable::accessibility()`] replace this tree.
+    pub fn with_accessibility(mut self, accessibility: AccessibilityTree) -> Self {
+        self.accessibility = Some(accessibility);
+        self
+    }
+
+    /// Registers `font` with the canvas. Fonts can also be registered later
+    /// with [`Command::RegisterFont`].
+    pub fn with_font(mut self, font: CanvasFont) -> Self {
+        self.fonts.push(font);
         self
     }
 
     pub fn finish(self) -> StyledWidget<Canvas> {
-        StyledWidget::from(self.canvas.unwrap())
+        let mut canvas = self.canvas.unwrap();
+        canvas.on_event = self.on_event;
+        canvas.show_statistics = self.show_statistics;
+        canvas.accessibility = self.accessibility;
+        canvas.fonts = self.fonts;
+        if let Some(statistics) = self.statistics {
+            canvas.statistics = statistics;
+        }
+        StyledWidget::from(canvas)
     }
 }
 
@@ -251,6 +1402,42 @@ impl<S: KeyedStorage<()>> ContentBuilder<(), S> for Builder<S> {
         Builder {
             storage,
             canvas: None,
+            on_event: None,
+            statistics: None,
+            show_statistics: false,
+            accessibility: None,
+            fonts: Vec::new(),
+        }
+    }
+}
+
+/// The portion of a [`Canvas`] that needs to be redrawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Invalidation {
+    Everything,
+    Region(Rect<f32, Scaled>),
+}
+
+impl Invalidation {
+    /// Returns the union of `self` and `other`.
+    pub(crate) fn union(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Region(a), Self::Region(b)) => {
+                let (a, b) = (a.as_extents(), b.as_extents());
+                Self::Region(Rect::from(ExtentsRect::new(
+                    Point::new(a.origin.x.min(b.origin.x), a.origin.y.min(b.origin.y)),
+                    Point::new(a.extent.x.max(b.extent.x), a.extent.y.max(b.extent.y)),
+                )))
+            }
+            _ => Self::Everything,
+        }
+    }
+
+    /// Returns the dirty region to report to the [`Renderable`].
+    pub(crate) const fn region(self) -> Option<Rect<f32, Scaled>> {
+        match self {
+            Self::Everything => None,
+            Self::Region(region) => Some(region),
         }
     }
 }
@@ -0,0 +1,167 @@
+use gooey::core::{
+    figures::{ExtentsRect, Point, Rect},
+    Scaled,
+};
+
+/// A sequence of connected line segments.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path {
+    points: Vec<Point<f32, Scaled>>,
+    closed: bool,
+}
+
+impl Path {
+    /// Returns a new path starting at `start`.
+    #[must_use]
+    pub fn new(start: Point<f32, Scaled>) -> Self {
+        Self {
+            points: vec![start],
+            closed: false,
+        }
+    }
+
+    /// Returns a new path connecting `points` in order.
+    #[must_use]
+    pub fn from_points(points: impl IntoIterator<Item = Point<f32, Scaled>>) -> Self {
+        Self {
+            points: points.into_iter().collect(),
+            closed: false,
+        }
+    }
+
+    /// Adds a segment from the last point to `point`.
+    #[must_use]
+    pub fn line_to(mut self, point: Point<f32, Scaled>) -> Self {
+        self.points.push(point);
+        self
+    }
+
+    /// Connects the last point back to the first.
+    #[must_use]
+    pub fn close(mut self) -> Self {
+        self.closed = true;
+        self
+    }
+
+    /// Returns the points of this path.
+    #[must_use]
+    pub fn points(&self) -> &[Point<f32, Scaled>] {
+        &self.points
+    }
+
+    /// Returns true if the last point connects back to the first.
+    #[must_use]
+    pub const fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Returns the line segments of this path, including the closing segment.
+    pub fn segments(&self) -> impl Iterator<Item = (Point<f32, Scaled>, Point<f32, Scaled>)> + '_ {
+        let closing = if self.closed && self.points.len() > 2 {
+            Some((self.points[self.points.len() - 1], self.points[0]))
+        } else {
+            None
+        };
+        self.points
+            .windows(2)
+            .map(|pair| (pair[0], pair[1]))
+            .chain(closing)
+    }
+
+    /// Returns the smallest rectangle containing every point, or `None` if the
+    /// path is empty.
+    #[must_use]
+    pub fn bounds(&self) -> Option<Rect<f32, Scaled>> {
+        let first = *self.points.first()?;
+        let (min, max) = self
+            .points
+            .iter()
+            .fold((first, first), |(min, max), point| {
+                (
+                    Point::new(min.x.min(point.x), min.y.min(point.y)),
+                    Point::new(max.x.max(point.x), max.y.max(point.y)),
+                )
+            });
+        Some(Rect::from(ExtentsRect::new(min, max)))
+    }
+
+    /// Returns the total length of the path's segments.
+    #[must_use]
+    pub fn length(&self) -> f32 {
+        self.segments().map(|(a, b)| distance(a, b)).sum()
+    }
+
+    /// Returns the point `distance` along this path, and the direction of the
+    /// path at that point in radians clockwise from the positive x axis.
+    /// Returns `None` if `distance` is negative or beyond the end of the path.
+    #[must_use]
+    pub fn point_at(&self, distance: f32) -> Option<(Point<f32, Scaled>, f32)> {
+        if distance < 0. {
+            return None;
+        }
+        let mut remaining = distance;
+        for (a, b) in self.segments() {
+            let length = self::distance(a, b);
+            if length <= 0. {
+                continue;
+            } else if remaining <= length {
+                let fraction = remaining / length;
+                return Some((
+                    Point::new(a.x + (b.x - a.x) * fraction, a.y + (b.y - a.y) * fraction),
+                    (b.y - a.y).atan2(b.x - a.x),
+                ));
+            }
+            remaining -= length;
+        }
+        None
+    }
+
+    /// Returns the distance from `point` to the closest segment of this path.
+    #[must_use]
+    pub fn distance_to(&self, point: Point<f32, Scaled>) -> Option<f32> {
+        if self.points.len() == 1 {
+            return Some(distance(self.points[0], point));
+        }
+        self.segments()
+            .map(|(a, b)| distance_to_segment(point, a, b))
+            .reduce(f32::min)
+    }
+
+    /// Returns true if `point` is inside the area enclosed by this path, using
+    /// the even-odd rule. The path is treated as closed.
+    #[must_use]
+    pub fn contains(&self, point: Point<f32, Scaled>) -> bool {
+        let mut inside = false;
+        let count = self.points.len();
+        for index in 0..count {
+            let a = self.points[index];
+            let b = self.points[(index + 1) % count];
+            if (a.y > point.y) != (b.y > point.y)
+                && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+            {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+}
+
+fn distance(a: Point<f32, Scaled>, b: Point<f32, Scaled>) -> f32 {
+    (b.x - a.x).hypot(b.y - a.y)
+}
+
+fn distance_to_segment(
+    point: Point<f32, Scaled>,
+    a: Point<f32, Scaled>,
+    b: Point<f32, Scaled>,
+) -> f32 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let length_squared = dx * dx + dy * dy;
+    if length_squared <= f32::EPSILON {
+        return distance(point, a);
+    }
+    let t = (((point.x - a.x) * dx + (point.y - a.y) * dy) / length_squared)
+        .max(0.)
+        .min(1.);
+    distance(point, Point::new(a.x + dx * t, a.y + dy * t))
+}
@@ -0,0 +1,111 @@
+use gooey::core::{
+    figures::{Point, Vector},
+    Scaled,
+};
+
+use crate::Transform;
+
+/// A mouse, pen or touch interaction with a [`Canvas`](crate::Canvas).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PointerEvent {
+    /// What happened.
+    pub kind: PointerEventKind,
+    /// The location of the pointer, relative to the canvas.
+    pub location: Point<f32, Scaled>,
+    /// Identifies the pointer among those in contact at the same time, such
+    /// as each finger on a touch screen.
+    pub pointer_id: u32,
+    /// The kind of device the pointer belongs to.
+    pub device: PointerDevice,
+    /// The modifier keys held when the event happened.
+    ///
+    /// The rasterizer doesn't report modifier keys with mouse events, so on
+    /// Kludgine no modifiers are ever held.
+    pub modifiers: Modifiers,
+}
+
+impl PointerEvent {
+    /// Returns this event with its location mapped through `transform`.
+    #[must_use]
+    pub fn transformed(&self, transform: &Transform) -> Self {
+        let kind = match self.kind {
+            PointerEventKind::Wheel(delta) => PointerEventKind::Wheel(Vector::new(
+                delta.x * transform.scale,
+                delta.y * transform.scale,
+            )),
+            other => other,
+        };
+        Self {
+            kind,
+            location: transform.transform_point(self.location),
+            ..*self
+        }
+    }
+}
+
+/// The kind of a [`PointerEvent`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PointerEventKind {
+    /// A button was pressed.
+    Down(PointerButton),
+    /// The pointer moved.
+    Move,
+    /// A button was released.
+    Up(PointerButton),
+    /// The frontend took the pointer over, for example to scroll the page,
+    /// and won't report its release. Any press or drag it started should be
+    /// abandoned.
+    Cancel,
+    /// The scroll wheel or trackpad was scrolled by the contained amount.
+    ///
+    /// The rasterizer doesn't pass scroll events to widgets, so the Kludgine
+    /// frontend never sends this.
+    Wheel(Vector<f32, Scaled>),
+}
+
+/// The kind of device that produced a [`PointerEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointerDevice {
+    /// A mouse or trackpad.
+    Mouse,
+    /// A pen or stylus.
+    Pen,
+    /// A finger on a touch screen.
+    Touch,
+}
+
+/// The modifier keys held during a [`PointerEvent`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+    /// A shift key.
+    pub shift: bool,
+    /// A control key.
+    pub control: bool,
+    /// An alt or option key.
+    pub alt: bool,
+    /// A Windows, command or meta key.
+    pub meta: bool,
+}
+
+/// A button on a pointing device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointerButton {
+    /// The left mouse button, or the contact of a pen or touch.
+    Primary,
+    /// The right mouse button.
+    Secondary,
+    /// The middle mouse button.
+    Middle,
+    /// Any other button, by its frontend-specific index.
+    Other(u16),
+}
+
+/// Whether an event was handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventStatus {
+    /// The event was not handled, and it will be sent to the
+    /// [`Canvas`](crate::Canvas)'s event callback.
+    Ignored,
+    /// The event was handled. The canvas will be redrawn.
+    Processed,
+}
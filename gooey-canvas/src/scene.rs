@@ -0,0 +1,486 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Debug,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use gooey::{
+    core::{
+        assets::Image,
+        figures::{ExtentsRect, Point, Rect, Rectlike, Size, SizedRect},
+        styles::Color,
+        Context, Scaled,
+    },
+    frontends::rasterizer::ContentArea,
+    renderer::{Renderer, StrokeOptions, TextOptions},
+};
+
+use crate::{Canvas, CanvasRenderer, Command, Path, Renderable, Transform};
+
+/// A retained collection of shapes that renders itself onto a [`Canvas`].
+///
+/// `Scene` is a handle: clones refer to the same nodes. Pass one clone to
+/// [`Canvas::new()`] and keep another to modify the scene. Each modification
+/// requests a redraw of the canvas, and the canvas is otherwise only redrawn
+/// when the frontend needs it to be.
+#[derive(Clone, Default)]
+pub struct Scene {
+    data: Arc<Mutex<SceneData>>,
+}
+
+#[derive(Default)]
+struct SceneData {
+    nodes: BTreeMap<NodeId, Node>,
+    next_id: u64,
+    context: Option<Context<Canvas>>,
+    /// The transform from scene coordinates to canvas coordinates, as of the
+    /// last frame.
+    transform: Option<Transform>,
+    redraw_requested: bool,
+}
+
+impl Debug for Scene {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Scene")
+            .field("nodes", &self.data().nodes)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Identifies a [`Node`] within a [`Scene`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(u64);
+
+/// A shape within a [`Scene`].
+#[derive(Debug, Clone)]
+pub struct Node {
+    /// What to draw.
+    pub shape: Shape,
+    /// Maps the shape's coordinates into the scene.
+    pub transform: Transform,
+    /// Nodes with a higher z-index are drawn above nodes with a lower one.
+    /// Nodes with the same z-index are drawn in the order they were inserted.
+    pub z_index: i32,
+    /// Hidden nodes are neither drawn nor hit tested.
+    pub visible: bool,
+    /// The size of the shape, measured the last time it was drawn. Only used
+    /// for text.
+    measured: Option<Rect<f32, Scaled>>,
+}
+
+/// The contents of a [`Node`].
+#[derive(Debug, Clone)]
+pub enum Shape {
+    /// A rectangle that can be filled, stroked, or both.
+    Rect {
+        /// The rectangle.
+        rect: Rect<f32, Scaled>,
+        /// The color to fill the rectangle with.
+        fill: Option<Color>,
+        /// The stroke to outline the rectangle with.
+        stroke: Option<StrokeOptions>,
+    },
+    /// A stroked [`Path`].
+    Path {
+        /// The path.
+        path: Path,
+        /// The stroke to draw the path with.
+        stroke: StrokeOptions,
+    },
+    /// A line of text, with its baseline starting at the origin.
+    Text {
+        /// The text.
+        text: String,
+        /// The options used to render the text.
+        options: TextOptions,
+    },
+    /// An image, with its top-left corner at the origin.
+    Image {
+        /// The image.
+        image: Image,
+        /// The size of the image, used for hit testing. The image is always
+        /// drawn at its natural size.
+        size: Size<f32, Scaled>,
+    },
+}
+
+impl Node {
+    /// Returns a new visible node with no transform and a z-index of 0.
+    #[must_use]
+    pub fn new(shape: Shape) -> Self {
+        Self {
+            shape,
+            transform: Transform::identity(),
+            z_index: 0,
+            visible: true,
+            measured: None,
+        }
+    }
+
+    /// Sets the transform and returns self.
+    #[must_use]
+    pub fn with_transform(mut self, transform: Transform) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    /// Sets the z-index and returns self.
+    #[must_use]
+    pub fn with_z_index(mut self, z_index: i32) -> Self {
+        self.z_index = z_index;
+        self
+    }
+
+    /// Sets the visibility and returns self.
+    #[must_use]
+    pub fn with_visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    /// Returns the bounds of this node in scene coordinates. Text nodes have no
+    /// bounds until they have been drawn.
+    #[must_use]
+    pub fn bounds(&self) -> Option<Rect<f32, Scaled>> {
+        let local = match &self.shape {
+            Shape::Rect { rect, stroke, .. } => {
+                let outset = stroke
+                    .as_ref()
+                    .map_or(0., |stroke| stroke.line_width.get() / 2.);
+                Some(outset_rect(*rect, outset))
+            }
+            Shape::Path { path, stroke } => path
+                .bounds()
+                .map(|bounds| outset_rect(bounds, stroke.line_width.get() / 2.)),
+            Shape::Text { .. } => self.measured,
+            Shape::Image { size, .. } => Some(Rect::from(SizedRect::new(Point::default(), *size))),
+        }?;
+        Some(self.transform.transform_rect(local))
+    }
+
+    /// Returns true if `point`, in scene coordinates, is over this node.
+    #[must_use]
+    pub fn hit_test(&self, point: Point<f32, Scaled>) -> bool {
+        if !self.visible {
+            return false;
+        }
+        match &self.shape {
+            Shape::Path { path, stroke } => {
+                let local = self.transform.inverse().transform_point(point);
+                path.distance_to(local).map_or(false, |distance| {
+                    distance <= stroke.line_width.get() / 2. + HIT_TOLERANCE
+                })
+            }
+            _ => self.bounds().map_or(false, |bounds| {
+                let bounds = bounds.as_extents();
+                point.x >= bounds.origin.x
+                    && point.y >= bounds.origin.y
+                    && point.x <= bounds.extent.x
+                    && point.y <= bounds.extent.y
+            }),
+        }
+    }
+
+    fn render(&mut self, renderer: &CanvasRenderer) {
+        let renderer = renderer.transformed(&self.transform);
+        match &self.shape {
+            Shape::Rect { rect, fill, stroke } => {
+                if let Some(fill) = fill {
+                    renderer.fill_rect(rect, *fill);
+                }
+                if let Some(stroke) = stroke {
+                    renderer.stroke_rect(rect, stroke);
+                }
+            }
+            Shape::Path { path, stroke } => renderer.stroke_path(path, stroke),
+            Shape::Text { text, options } => {
+                renderer.render_text(text, Point::<f32, Scaled>::default(), options);
+                let metrics = renderer.measure_text(text, options);
+                self.measured = Some(Rect::from(ExtentsRect::new(
+                    Point::new(0., -metrics.ascent.get()),
+                    Point::new(metrics.width.get(), metrics.descent.get().abs()),
+                )));
+            }
+            Shape::Image { image, .. } => {
+                renderer.draw_image(image, Point::<f32, Scaled>::default());
+            }
+        }
+    }
+}
+
+/// Extra distance, in scene coordinates, that still counts as a hit on a
+/// path.
+const HIT_TOLERANCE: f32 = 2.;
+
+fn outset_rect(rect: Rect<f32, Scaled>, amount: f32) -> Rect<f32, Scaled> {
+    let rect = rect.as_extents();
+    Rect::from(ExtentsRect::new(
+        Point::new(rect.origin.x - amount, rect.origin.y - amount),
+        Point::new(rect.extent.x + amount, rect.extent.y + amount),
+    ))
+}
+
+impl Scene {
+    /// Returns a new, empty scene.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn data(&self) -> MutexGuard<'_, SceneData> {
+        self.data.lock().unwrap()
+    }
+
+    /// Adds `node` to the scene and returns its id.
+    pub fn insert(&self, node: Node) -> NodeId {
+        let mut data = self.data();
+        let id = NodeId(data.next_id);
+        data.next_id += 1;
+        let bounds = node.bounds();
+        data.nodes.insert(id, node);
+        data.changed(&[bounds]);
+        id
+    }
+
+    /// Removes and returns the node `id`.
+    pub fn remove(&self, id: NodeId) -> Option<Node> {
+        let mut data = self.data();
+        let node = data.nodes.remove(&id);
+        if let Some(node) = &node {
+            data.changed(&[node.bounds()]);
+        }
+        node
+    }
+
+    /// Removes all nodes.
+    pub fn clear(&self) {
+        let mut data = self.data();
+        data.nodes.clear();
+        data.changed(&[None]);
+    }
+
+    /// Returns a copy of the node `id`.
+    #[must_use]
+    pub fn get(&self, id: NodeId) -> Option<Node> {
+        self.data().nodes.get(&id).cloned()
+    }
+
+    /// Calls `modify` with the node `id` and returns its result, or `None` if
+    /// the node doesn't exist.
+    ///
+    /// `modify` is called with a copy of the node, without the scene locked,
+    /// so it may use the scene. The copy replaces the node afterwards, unless
+    /// the node was removed in the meantime.
+    pub fn update<R, F: FnOnce(&mut Node) -> R>(&self, id: NodeId, modify: F) -> Option<R> {
+        let mut node = self.get(id)?;
+        let previous_bounds = node.bounds();
+        let result = modify(&mut node);
+
+        let mut data = self.data();
+        let bounds = node.bounds();
+        if let Some(existing) = data.nodes.get_mut(&id) {
+            *existing = node;
+            data.changed(&[previous_bounds, bounds]);
+        }
+        Some(result)
+    }
+
+    /// Returns the number of nodes in the scene.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.data().nodes.len()
+    }
+
+    /// Returns true if the scene has no nodes.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.data().nodes.is_empty()
+    }
+
+    /// Returns the topmost visible node at `point`, in scene coordinates.
+    #[must_use]
+    pub fn hit_test(&self, point: Point<f32, Scaled>) -> Option<NodeId> {
+        self.hit_test_all(point).into_iter().next()
+    }
+
+    /// Returns every visible node at `point`, in scene coordinates, from top
+    /// to bottom.
+    #[must_use]
+    pub fn hit_test_all(&self, point: Point<f32, Scaled>) -> Vec<NodeId> {
+        let data = self.data();
+        let mut hits = data
+            .nodes
+            .iter()
+            .filter(|(_, node)| node.hit_test(point))
+            .map(|(id, node)| (node.z_index, *id))
+            .collect::<Vec<_>>();
+        hits.sort_unstable();
+        hits.into_iter().rev().map(|(_, id)| id).collect()
+    }
+}
+
+impl SceneData {
+    /// Requests a redraw of the areas covered by `bounds`, in scene
+    /// coordinates. A `None` bounds, such as a text node that hasn't been
+    /// measured yet, redraws the entire canvas.
+    fn changed(&mut self, bounds: &[Option<Rect<f32, Scaled>>]) {
+        if self.redraw_requested {
+            return;
+        }
+        let context = match &self.context {
+            Some(context) => context,
+            None => return,
+        };
+
+        let regions = bounds
+            .iter()
+            .map(|bounds| {
+                let transform = self.transform?;
+                // Leave room for antialiasing around the edges.
+                Some(outset_rect(transform.transform_rect((*bounds)?), 1.))
+            })
+            .collect::<Option<Vec<_>>>();
+        match regions {
+            Some(regions) => {
+                for region in regions {
+                    context.send_command(Command::Invalidate(region));
+                }
+            }
+            None => {
+                self.redraw_requested = true;
+                context.send_command(Command::Refresh);
+            }
+        }
+    }
+}
+
+impl Renderable for Scene {
+    fn initialize(&mut self, context: &Context<Canvas>) {
+        self.data().context = Some(context.clone());
+    }
+
+    fn render(&mut self, renderer: CanvasRenderer, _content_area: &ContentArea) {
+        let mut data = self.data();
+        data.redraw_requested = false;
+        data.transform = Some(renderer.transform());
+
+        let dirty = renderer.dirty_region().map(|region| region.as_extents());
+        let mut order = data
+            .nodes
+            .iter()
+            .filter(|(_, node)| node.visible)
+            .map(|(id, node)| (node.z_index, *id))
+            .collect::<Vec<_>>();
+        order.sort_unstable();
+        for (_, id) in order {
+            let node = data.nodes.get_mut(&id).unwrap();
+            // Skip nodes that are entirely outside of the area being redrawn.
+            if let (Some(dirty), Some(bounds)) = (&dirty, node.bounds()) {
+                if dirty.intersection(&bounds.as_extents()).is_none() {
+                    continue;
+                }
+            }
+            node.render(&renderer);
+        }
+    }
+
+    fn teardown(&mut self) {
+        self.data().context = None;
+    }
+}
+
+impl From<Shape> for Node {
+    fn from(shape: Shape) -> Self {
+        Self::new(shape)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gooey::core::figures::{Figure, Vector};
+
+    use super::*;
+
+    fn rect(x: f32, y: f32, width: f32, height: f32) -> Shape {
+        Shape::Rect {
+            rect: Rect::from(SizedRect::new(Point::new(x, y), Size::new(width, height))),
+            fill: Some(Color::RED),
+            stroke: None,
+        }
+    }
+
+    #[test]
+    fn hit_tests_from_top_to_bottom() {
+        let scene = Scene::new();
+        let bottom = scene.insert(Node::new(rect(0., 0., 100., 100.)));
+        let top = scene.insert(Node::new(rect(50., 50., 100., 100.)));
+        let raised = scene.insert(Node::new(rect(0., 0., 20., 20.)).with_z_index(1));
+
+        assert_eq!(scene.hit_test(Point::new(75., 75.)), Some(top));
+        assert_eq!(scene.hit_test_all(Point::new(75., 75.)), vec![top, bottom]);
+        assert_eq!(
+            scene.hit_test_all(Point::new(10., 10.)),
+            vec![raised, bottom]
+        );
+        assert_eq!(scene.hit_test(Point::new(200., 10.)), None);
+    }
+
+    #[test]
+    fn hidden_nodes_are_not_hit() {
+        let scene = Scene::new();
+        let node = scene.insert(Node::new(rect(0., 0., 10., 10.)).with_visible(false));
+        assert_eq!(scene.hit_test(Point::new(5., 5.)), None);
+        scene.update(node, |node| node.visible = true);
+        assert_eq!(scene.hit_test(Point::new(5., 5.)), Some(node));
+    }
+
+    #[test]
+    fn hit_tests_use_the_node_transform() {
+        let scene = Scene::new();
+        let node = scene.insert(
+            Node::new(rect(0., 0., 10., 10.))
+                .with_transform(Transform::new(2., Vector::new(100., 0.))),
+        );
+        assert_eq!(scene.hit_test(Point::new(5., 5.)), None);
+        assert_eq!(scene.hit_test(Point::new(115., 15.)), Some(node));
+    }
+
+    #[test]
+    fn paths_are_hit_along_their_stroke() {
+        let scene = Scene::new();
+        let node = scene.insert(Node::new(Shape::Path {
+            path: Path::new(Point::new(0., 0.)).line_to(Point::new(100., 100.)),
+            stroke: StrokeOptions {
+                line_width: Figure::new(4.),
+                ..StrokeOptions::default()
+            },
+        }));
+        assert_eq!(scene.hit_test(Point::new(50., 51.)), Some(node));
+        // Inside the path's bounds, but far from the line.
+        assert_eq!(scene.hit_test(Point::new(90., 10.)), None);
+    }
+
+    #[test]
+    fn unmeasured_text_is_not_hit() {
+        let scene = Scene::new();
+        scene.insert(Node::new(Shape::Text {
+            text: String::from("text"),
+            options: TextOptions::default(),
+        }));
+        assert_eq!(scene.hit_test(Point::default()), None);
+    }
+
+    #[test]
+    fn update_can_use_the_scene() {
+        let scene = Scene::new();
+        let node = scene.insert(Node::new(rect(0., 0., 10., 10.)));
+        let other = scene.insert(Node::new(rect(20., 0., 10., 10.)));
+        let result = scene.update(node, |node| {
+            node.z_index = scene.get(other).unwrap().z_index + 1;
+            scene.len()
+        });
+        assert_eq!(result, Some(2));
+        assert_eq!(scene.get(node).unwrap().z_index, 1);
+        assert_eq!(scene.update(NodeId(42), |_| ()), None);
+    }
+}
@@ -0,0 +1,157 @@
+use gooey::core::{
+    figures::{DisplayScale, Displayable, ExtentsRect, Point, Rect, Rectlike, Size},
+    Pixels, Scaled,
+};
+
+/// How images are resampled when they don't line up with device pixels. Set
+/// with
+/// [`CanvasRenderer::with_image_smoothing()`](crate::CanvasRenderer::with_image_smoothing).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageSmoothing {
+    /// Images are drawn with the nearest pixel, keeping pixel art crisp.
+    /// Images are also positioned on whole device pixels.
+    Disabled,
+    /// Images are interpolated quickly.
+    Low,
+    /// Images are interpolated with a balance of quality and speed.
+    Medium,
+    /// Images are interpolated with the best quality available.
+    High,
+}
+
+impl Default for ImageSmoothing {
+    fn default() -> Self {
+        Self::Low
+    }
+}
+
+impl ImageSmoothing {
+    /// Returns the value of the canvas `imageSmoothingQuality` property for
+    /// this level, or `None` if smoothing is disabled.
+    #[must_use]
+    pub const fn css_name(self) -> Option<&'static str> {
+        match self {
+            Self::Disabled => None,
+            Self::Low => Some("low"),
+            Self::Medium => Some("medium"),
+            Self::High => Some("high"),
+        }
+    }
+}
+
+/// Returns `point` moved to the nearest whole device pixel.
+pub(crate) fn snap_point(
+    point: Point<f32, Scaled>,
+    scale: &DisplayScale<f32>,
+) -> Point<f32, Scaled> {
+    let pixels = point.to_pixels(scale);
+    Point::<f32, Pixels>::new(pixels.x.round(), pixels.y.round()).to_scaled(scale)
+}
+
+/// Returns `point` moved so that a line of `line_width` through it covers
+/// whole device pixels: lines an odd number of pixels wide are centered on a
+/// pixel, and other lines between two pixels.
+pub(crate) fn snap_stroke_point(
+    point: Point<f32, Scaled>,
+    line_width: f32,
+    scale: &DisplayScale<f32>,
+) -> Point<f32, Scaled> {
+    let width = Size::<f32, Scaled>::new(line_width, line_width)
+        .to_pixels(scale)
+        .width
+        .round()
+        .max(1.);
+    let pixels = point.to_pixels(scale);
+    let snap = |coordinate: f32| {
+        if width % 2. == 1. {
+            coordinate.floor() + 0.5
+        } else {
+            coordinate.round()
+        }
+    };
+    Point::<f32, Pixels>::new(snap(pixels.x), snap(pixels.y)).to_scaled(scale)
+}
+
+/// Returns `rect` with its edges moved to the nearest whole device pixels.
+pub(crate) fn snap_rect(rect: Rect<f32, Scaled>, scale: &DisplayScale<f32>) -> Rect<f32, Scaled> {
+    let rect = rect.as_extents();
+    Rect::from(ExtentsRect::new(
+        snap_point(rect.origin, scale),
+        snap_point(rect.extent, scale),
+    ))
+}
+
+/// Returns `rect` with its edges moved so that a stroke of `line_width`
+/// covers whole device pixels.
+pub(crate) fn snap_stroke_rect(
+    rect: Rect<f32, Scaled>,
+    line_width: f32,
+    scale: &DisplayScale<f32>,
+) -> Rect<f32, Scaled> {
+    let rect = rect.as_extents();
+    Rect::from(ExtentsRect::new(
+        snap_stroke_point(rect.origin, line_width, scale),
+        snap_stroke_point(rect.extent, line_width, scale),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use gooey::core::figures::{Scale, SizedRect};
+
+    use super::*;
+
+    /// Two device pixels for every scaled unit.
+    fn doubled() -> DisplayScale<f32> {
+        DisplayScale::new(Scale::new(2.), Scale::new(1.))
+    }
+
+    fn edges(rect: Rect<f32, Scaled>) -> (f32, f32, f32, f32) {
+        let rect = rect.as_extents();
+        (rect.origin.x, rect.origin.y, rect.extent.x, rect.extent.y)
+    }
+
+    #[test]
+    fn points_snap_to_device_pixels() {
+        let scale = doubled();
+        assert_eq!(
+            snap_point(Point::new(1.2, 3.4), &scale),
+            Point::new(1., 3.5)
+        );
+        assert_eq!(
+            snap_point(Point::new(-0.3, 0.8), &scale),
+            Point::new(-0.5, 1.)
+        );
+    }
+
+    #[test]
+    fn odd_strokes_are_centered_on_pixels() {
+        let scale = doubled();
+        // Half a unit is one device pixel wide, so it's centered on a pixel.
+        assert_eq!(
+            snap_stroke_point(Point::new(1.2, 3.4), 0.5, &scale),
+            Point::new(1.25, 3.25)
+        );
+        // One unit is two device pixels wide, so it's centered between them.
+        assert_eq!(
+            snap_stroke_point(Point::new(1.2, 3.4), 1., &scale),
+            Point::new(1., 3.5)
+        );
+        // Strokes thinner than a device pixel are treated as one pixel wide.
+        assert_eq!(
+            snap_stroke_point(Point::new(1.2, 3.4), 0.1, &scale),
+            Point::new(1.25, 3.25)
+        );
+    }
+
+    #[test]
+    fn rects_snap_their_edges() {
+        let scale = doubled();
+        let rect = Rect::from(SizedRect::new(Point::new(0.2, 0.6), Size::new(3.5, 1.1)));
+        assert_eq!(edges(snap_rect(rect, &scale)), (0., 0.5, 3.5, 1.5));
+        assert_eq!(
+            edges(snap_stroke_rect(rect, 0.5, &scale)),
+            (0.25, 0.75, 3.75, 1.75)
+        );
+    }
+}
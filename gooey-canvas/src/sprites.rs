@@ -0,0 +1,265 @@
+use std::{any::Any, collections::HashMap};
+
+use gooey::{
+    core::{
+        assets::Image,
+        figures::{Displayable, Point, Rect, Rectlike, Size, SizedRect, Vector},
+        Pixels, Scaled,
+    },
+    frontends::rasterizer::ContentArea,
+    renderer::Renderer,
+};
+
+use crate::{CanvasRenderer, Renderable, Transform};
+
+/// An [`Image`] divided into frames.
+#[derive(Debug, Clone)]
+pub struct SpriteSheet {
+    image: Image,
+    frames: Vec<Rect<u32, Pixels>>,
+    names: HashMap<String, usize>,
+}
+
+impl SpriteSheet {
+    /// Returns a sprite sheet for `image` with no frames.
+    #[must_use]
+    pub fn new(image: Image) -> Self {
+        Self {
+            image,
+            frames: Vec::new(),
+            names: HashMap::new(),
+        }
+    }
+
+    /// Returns a sprite sheet for `image` divided into a grid of `columns` by
+    /// `rows` frames of `frame_size`. Frames are numbered left to right, top
+    /// to bottom.
+    #[must_use]
+    pub fn grid(image: Image, frame_size: Size<u32, Pixels>, columns: u32, rows: u32) -> Self {
+        let mut sheet = Self::new(image);
+        for row in 0..rows {
+            for column in 0..columns {
+                sheet.add_frame(Rect::from(SizedRect::new(
+                    Point::new(column * frame_size.width, row * frame_size.height),
+                    frame_size,
+                )));
+            }
+        }
+        sheet
+    }
+
+    /// Adds a frame named `name` and returns self.
+    #[must_use]
+    pub fn with_frame(mut self, name: impl Into<String>, region: Rect<u32, Pixels>) -> Self {
+        let index = self.add_frame(region);
+        self.names.insert(name.into(), index);
+        self
+    }
+
+    /// Adds a frame covering `region` of the image and returns its index.
+    pub fn add_frame(&mut self, region: Rect<u32, Pixels>) -> usize {
+        self.frames.push(region);
+        self.frames.len() - 1
+    }
+
+    /// Names the frame at `index`, so that it can be looked up with
+    /// [`frame_index()`](Self::frame_index).
+    pub fn name_frame(&mut self, name: impl Into<String>, index: usize) {
+        self.names.insert(name.into(), index);
+    }
+
+    /// Returns the index of the frame named `name`.
+    #[must_use]
+    pub fn frame_index(&self, name: &str) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    /// Returns the region of the image covered by the frame at `index`.
+    #[must_use]
+    pub fn frame(&self, index: usize) -> Option<Rect<u32, Pixels>> {
+        self.frames.get(index).copied()
+    }
+
+    /// Returns the number of frames.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Returns true if there are no frames.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Returns the image frames are drawn from.
+    #[must_use]
+    pub const fn image(&self) -> &Image {
+        &self.image
+    }
+
+    /// Draws the frame at `index` with its top-left corner at `location`.
+    pub fn draw(
+        &self,
+        renderer: &CanvasRenderer,
+        index: usize,
+        location: impl Displayable<f32, Pixels = Point<f32, Pixels>>,
+    ) {
+        if let Some(frame) = self.frame(index) {
+            renderer.draw_image_region(&self.image, frame, location);
+        }
+    }
+}
+
+/// A grid of tiles drawn from a [`SpriteSheet`].
+///
+/// Only the tiles within the visible area of the canvas are drawn, and they
+/// are drawn with a single [`CanvasRenderer::draw_image_regions()`] call.
+///
+/// Kludgine can only draw images at their natural size, so there the map is
+/// moved by a scaled renderer's transform but its tiles aren't scaled.
+#[derive(Debug, Clone)]
+pub struct TileMap {
+    sheet: SpriteSheet,
+    tile_size: Size<u32, Pixels>,
+    columns: u32,
+    rows: u32,
+    tiles: Vec<Option<usize>>,
+}
+
+/// A message that updates a [`TileMap`], sent with
+/// [`Command::message()`](crate::Command::message).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileMapCommand {
+    /// Sets the frame of the tile at a column and row. `None` leaves the tile
+    /// empty.
+    SetTile {
+        /// The column of the tile.
+        column: u32,
+        /// The row of the tile.
+        row: u32,
+        /// The frame to draw.
+        frame: Option<usize>,
+    },
+    /// Sets every tile to a frame.
+    Fill(Option<usize>),
+}
+
+impl TileMap {
+    /// Returns an empty tile map with `columns` by `rows` tiles of
+    /// `tile_size`.
+    #[must_use]
+    pub fn new(sheet: SpriteSheet, tile_size: Size<u32, Pixels>, columns: u32, rows: u32) -> Self {
+        Self {
+            sheet,
+            tile_size,
+            columns,
+            rows,
+            tiles: vec![None; columns as usize * rows as usize],
+        }
+    }
+
+    /// Returns the sprite sheet tiles are drawn from.
+    #[must_use]
+    pub const fn sheet(&self) -> &SpriteSheet {
+        &self.sheet
+    }
+
+    /// Returns the number of columns and rows.
+    #[must_use]
+    pub const fn dimensions(&self) -> (u32, u32) {
+        (self.columns, self.rows)
+    }
+
+    /// Returns the frame of the tile at `column` and `row`.
+    #[must_use]
+    pub fn tile(&self, column: u32, row: u32) -> Option<usize> {
+        self.index(column, row).and_then(|index| self.tiles[index])
+    }
+
+    /// Sets the frame of the tile at `column` and `row`. Tiles outside of the
+    /// map are ignored.
+    pub fn set_tile(&mut self, column: u32, row: u32, frame: Option<usize>) {
+        if let Some(index) = self.index(column, row) {
+            self.tiles[index] = frame;
+        }
+    }
+
+    /// Sets every tile to `frame`.
+    pub fn fill(&mut self, frame: Option<usize>) {
+        for tile in &mut self.tiles {
+            *tile = frame;
+        }
+    }
+
+    fn index(&self, column: u32, row: u32) -> Option<usize> {
+        if column < self.columns && row < self.rows {
+            Some(row as usize * self.columns as usize + column as usize)
+        } else {
+            None
+        }
+    }
+}
+
+impl Renderable for TileMap {
+    fn receive(&mut self, message: Box<dyn Any + Send + Sync>) {
+        if let Ok(command) = message.downcast::<TileMapCommand>() {
+            match *command {
+                TileMapCommand::SetTile { column, row, frame } => {
+                    self.set_tile(column, row, frame);
+                }
+                TileMapCommand::Fill(frame) => self.fill(frame),
+            }
+        }
+    }
+
+    fn render(&mut self, renderer: CanvasRenderer, _content_area: &ContentArea) {
+        // Without scaled images, scaled tiles would leave gaps between them,
+        // so the tiles are laid out at their natural size instead.
+        let renderer = if renderer.scales_images() {
+            renderer
+        } else {
+            renderer.transformed(&Transform::new(
+                1. / renderer.transform().scale,
+                Vector::default(),
+            ))
+        };
+        let tile_size = self.tile_size.cast::<f32>().to_scaled(&renderer.scale());
+        if tile_size.width <= 0. || tile_size.height <= 0. {
+            return;
+        }
+
+        let clip = renderer.clip_bounds();
+        let visible = renderer
+            .dirty_region()
+            .and_then(|dirty| {
+                dirty
+                    .intersection(&clip)
+                    .map(|visible| visible.as_extents())
+            })
+            .unwrap_or_else(|| clip.as_extents());
+        let first_column = (visible.origin.x / tile_size.width).floor().max(0.) as u32;
+        let first_row = (visible.origin.y / tile_size.height).floor().max(0.) as u32;
+        let last_column =
+            ((visible.extent.x / tile_size.width).ceil().max(0.) as u32).min(self.columns);
+        let last_row = ((visible.extent.y / tile_size.height).ceil().max(0.) as u32).min(self.rows);
+
+        let sheet = &self.sheet;
+        let tiles = &self.tiles;
+        let columns = self.columns as usize;
+        let regions = (first_row..last_row).flat_map(|row| {
+            (first_column..last_column).filter_map(move |column| {
+                let frame = tiles[row as usize * columns + column as usize]?;
+                let source = sheet.frame(frame)?;
+                Some((
+                    source,
+                    Point::<f32, Scaled>::new(
+                        column as f32 * tile_size.width,
+                        row as f32 * tile_size.height,
+                    ),
+                ))
+            })
+        });
+        renderer.draw_image_regions(&sheet.image, regions);
+    }
+}
@@ -0,0 +1,211 @@
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use gooey::{
+    core::{
+        figures::{Point, Rect, Size, SizedRect},
+        styles::Color,
+        Scaled,
+    },
+    renderer::{Renderer, TextOptions},
+};
+
+use crate::CanvasRenderer;
+
+/// A handle to the statistics collected while drawing a
+/// [`Canvas`](crate::Canvas). Clones refer to the same statistics.
+#[derive(Debug, Clone, Default)]
+pub struct CanvasStatistics {
+    data: Arc<Mutex<FrameStatistics>>,
+}
+
+/// Statistics about how a [`Canvas`](crate::Canvas) has been drawn.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrameStatistics {
+    /// The number of frames drawn.
+    pub frames_drawn: u64,
+    /// The number of redraw requests that were combined with a redraw that had
+    /// already been requested.
+    pub coalesced_redraws: u64,
+    /// How long [`Renderable::render()`](crate::Renderable::render) took for
+    /// the most recent frame.
+    pub last_render_duration: Duration,
+    /// A moving average of how long
+    /// [`Renderable::render()`](crate::Renderable::render) takes.
+    pub average_render_duration: Duration,
+    /// A moving average of the rate frames are drawn at, while frames are
+    /// being drawn continuously.
+    pub frames_per_second: f32,
+    /// The primitives drawn in the most recent frame.
+    pub primitives: PrimitiveCounts,
+}
+
+/// The number of each kind of primitive drawn in a frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrimitiveCounts {
+    /// Runs of text drawn: each call to [`Renderer::render_text()`], and each
+    /// glyph of text that is drawn a glyph at a time, like text along a path.
+    pub text: u32,
+    /// Calls to [`Renderer::fill_rect()`].
+    pub filled_rects: u32,
+    /// Calls to [`Renderer::stroke_rect()`].
+    pub stroked_rects: u32,
+    /// Line segments stroked: each call to [`Renderer::stroke_line()`], and
+    /// each segment of a stroked path.
+    pub lines: u32,
+    /// Images and image regions drawn.
+    pub images: u32,
+}
+
+/// The weight given to the newest sample in the moving averages.
+const SMOOTHING: f64 = 0.1;
+
+/// Frames further apart than this aren't considered continuous when
+/// measuring the frame rate.
+const MAX_FRAME_INTERVAL: Duration = Duration::from_millis(250);
+
+impl CanvasStatistics {
+    /// Returns a copy of the current statistics.
+    #[must_use]
+    pub fn snapshot(&self) -> FrameStatistics {
+        self.data.lock().unwrap().clone()
+    }
+
+    /// Resets all statistics.
+    pub fn reset(&self) {
+        *self.data.lock().unwrap() = FrameStatistics::default();
+    }
+
+    pub(crate) fn record_coalesced_redraw(&self) {
+        self.data.lock().unwrap().coalesced_redraws += 1;
+    }
+
+    /// Records a frame that took `render_duration` to render, drawn
+    /// `since_last_frame` after the previous frame.
+    pub(crate) fn record_frame(
+        &self,
+        render_duration: Duration,
+        since_last_frame: Option<Duration>,
+        primitives: PrimitiveCounts,
+    ) {
+        let mut data = self.data.lock().unwrap();
+        data.average_render_duration = if data.frames_drawn == 0 {
+            render_duration
+        } else {
+            Duration::from_secs_f64(
+                data.average_render_duration.as_secs_f64() * (1. - SMOOTHING)
+                    + render_duration.as_secs_f64() * SMOOTHING,
+            )
+        };
+        data.frames_drawn += 1;
+        data.last_render_duration = render_duration;
+        data.primitives = primitives;
+        if let Some(interval) = since_last_frame
+            .filter(|interval| *interval <= MAX_FRAME_INTERVAL && !interval.is_zero())
+        {
+            let fps = 1. / interval.as_secs_f32();
+            data.frames_per_second = if data.frames_per_second > 0. {
+                data.frames_per_second * (1. - SMOOTHING as f32) + fps * SMOOTHING as f32
+            } else {
+                fps
+            };
+        }
+    }
+}
+
+/// Counts the primitives drawn through a [`CanvasRenderer`] and its clones.
+#[derive(Debug, Default)]
+pub(crate) struct PrimitiveCounter {
+    text: AtomicU32,
+    filled_rects: AtomicU32,
+    stroked_rects: AtomicU32,
+    lines: AtomicU32,
+    images: AtomicU32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Primitive {
+    Text,
+    FilledRect,
+    StrokedRect,
+    Line,
+    Image,
+}
+
+impl PrimitiveCounter {
+    pub(crate) fn increment(&self, primitive: Primitive) {
+        let counter = match primitive {
+            Primitive::Text => &self.text,
+            Primitive::FilledRect => &self.filled_rects,
+            Primitive::StrokedRect => &self.stroked_rects,
+            Primitive::Line => &self.lines,
+            Primitive::Image => &self.images,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn counts(&self) -> PrimitiveCounts {
+        PrimitiveCounts {
+            text: self.text.load(Ordering::Relaxed),
+            filled_rects: self.filled_rects.load(Ordering::Relaxed),
+            stroked_rects: self.stroked_rects.load(Ordering::Relaxed),
+            lines: self.lines.load(Ordering::Relaxed),
+            images: self.images.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Draws `statistics` in the top-left corner of the canvas.
+pub(crate) fn render_overlay(renderer: &CanvasRenderer, statistics: &FrameStatistics) {
+    let lines = [
+        format!(
+            "{:.1} fps, render {:.2} ms (avg {:.2} ms)",
+            statistics.frames_per_second,
+            statistics.last_render_duration.as_secs_f64() * 1000.,
+            statistics.average_render_duration.as_secs_f64() * 1000.,
+        ),
+        format!(
+            "{} frames, {} coalesced redraws",
+            statistics.frames_drawn, statistics.coalesced_redraws
+        ),
+        format!(
+            "text {}, fills {}, strokes {}, lines {}, images {}",
+            statistics.primitives.text,
+            statistics.primitives.filled_rects,
+            statistics.primitives.stroked_rects,
+            statistics.primitives.lines,
+            statistics.primitives.images,
+        ),
+    ];
+
+    let options = TextOptions {
+        color: Color::WHITE,
+        ..TextOptions::default()
+    };
+    let metrics = renderer.measure_text(&lines[0], &options);
+    let line_height = metrics.ascent.get() + metrics.descent.get().abs() + 2.;
+    let width = lines
+        .iter()
+        .map(|line| renderer.measure_text(line, &options).width.get())
+        .fold(0., f32::max);
+
+    renderer.fill_rect(
+        &Rect::from(SizedRect::<f32, Scaled>::new(
+            Point::default(),
+            Size::new(width + 8., line_height * lines.len() as f32 + 8.),
+        )),
+        Color::BLACK,
+    );
+    for (index, line) in lines.iter().enumerate() {
+        renderer.render_text(
+            line,
+            Point::<f32, Scaled>::new(4., 4. + line_height * index as f32 + metrics.ascent.get()),
+            &options,
+        );
+    }
+}
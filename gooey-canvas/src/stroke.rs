@@ -0,0 +1,342 @@
+use gooey::core::{figures::Point, Scaled};
+
+/// How strokes are drawn, beyond their color and width. Set with
+/// [`CanvasRenderer::with_stroke_style()`](crate::CanvasRenderer::with_stroke_style).
+///
+/// The rasterizer doesn't expose Kludgine's tessellator, so Kludgine draws
+/// joins and round caps by filling them one pixel row at a time. Their edges
+/// aren't antialiased.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrokeStyle {
+    /// Alternating lengths of dashes and gaps, starting with a dash. A list
+    /// with an odd number of lengths is repeated to make it even. An empty
+    /// list draws solid strokes.
+    pub dashes: Vec<f32>,
+    /// How far into the dash pattern strokes start.
+    pub dash_offset: f32,
+    /// How the ends of strokes and dashes are drawn.
+    pub cap: LineCap,
+    /// How the corners of paths and rectangles are drawn.
+    pub join: LineJoin,
+    /// The longest a [miter join](LineJoin::Miter) may be, as a multiple of
+    /// the stroke width, before it is drawn as a bevel.
+    pub miter_limit: f32,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self {
+            dashes: Vec::new(),
+            dash_offset: 0.,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            miter_limit: 10.,
+        }
+    }
+}
+
+impl StrokeStyle {
+    /// Returns a style drawing dashes and gaps of the given lengths.
+    pub fn dashed(dashes: impl IntoIterator<Item = f32>) -> Self {
+        Self {
+            dashes: dashes.into_iter().collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Sets how far into the dash pattern strokes start, and returns self.
+    /// Animating the offset makes a selection marquee's dashes march.
+    #[must_use]
+    pub fn with_dash_offset(mut self, offset: f32) -> Self {
+        self.dash_offset = offset;
+        self
+    }
+
+    /// Sets the cap style and returns self.
+    #[must_use]
+    pub fn with_cap(mut self, cap: LineCap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// Sets the join style and returns self.
+    #[must_use]
+    pub fn with_join(mut self, join: LineJoin) -> Self {
+        self.join = join;
+        self
+    }
+
+    /// Sets the miter limit and returns self.
+    #[must_use]
+    pub fn with_miter_limit(mut self, miter_limit: f32) -> Self {
+        self.miter_limit = miter_limit;
+        self
+    }
+
+    /// Returns this style with its lengths multiplied by `scale`.
+    pub(crate) fn scaled(&self, scale: f32) -> Self {
+        Self {
+            dashes: self.dashes.iter().map(|length| length * scale).collect(),
+            dash_offset: self.dash_offset * scale,
+            ..self.clone()
+        }
+    }
+
+    /// Returns the dash pattern with an even number of lengths, or `None` if
+    /// strokes are solid.
+    pub(crate) fn dash_pattern(&self) -> Option<Vec<f32>> {
+        let total = self.dashes.iter().sum::<f32>();
+        if self
+            .dashes
+            .iter()
+            .any(|length| *length < 0. || !length.is_finite())
+            || total <= 0.
+            || !total.is_finite()
+        {
+            return None;
+        }
+
+        let mut pattern = self.dashes.clone();
+        if pattern.len() % 2 == 1 {
+            pattern.extend_from_slice(&self.dashes);
+        }
+        Some(pattern)
+    }
+}
+
+/// How the ends of a stroke are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineCap {
+    /// The stroke ends exactly at its end point.
+    Butt,
+    /// The stroke ends with a semicircle.
+    Round,
+    /// The stroke extends past its end point by half its width.
+    Square,
+}
+
+impl LineCap {
+    /// Returns the value of the canvas `lineCap` property for this cap.
+    #[must_use]
+    pub const fn css_name(self) -> &'static str {
+        match self {
+            Self::Butt => "butt",
+            Self::Round => "round",
+            Self::Square => "square",
+        }
+    }
+}
+
+/// How two connected segments of a stroke are joined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineJoin {
+    /// The outer edges are extended until they meet.
+    Miter,
+    /// The corner is rounded.
+    Round,
+    /// The corner is cut off.
+    Bevel,
+}
+
+impl LineJoin {
+    /// Returns the value of the canvas `lineJoin` property for this join.
+    #[must_use]
+    pub const fn css_name(self) -> &'static str {
+        match self {
+            Self::Miter => "miter",
+            Self::Round => "round",
+            Self::Bevel => "bevel",
+        }
+    }
+}
+
+/// The most steps a [`Dasher`] takes along a single polyline. Patterns much
+/// shorter than the lines they dash would otherwise take a very long time to
+/// split, or never finish once the pattern is too short to move forward in
+/// `f32`.
+#[cfg(feature = "frontend-kludgine")]
+const MAX_DASH_STEPS: usize = 100_000;
+
+/// Splits polylines into dashes, continuing the pattern from one line to the
+/// next.
+#[cfg(feature = "frontend-kludgine")]
+pub(crate) struct Dasher {
+    pattern: Vec<f32>,
+    index: usize,
+    remaining: f32,
+}
+
+#[cfg(feature = "frontend-kludgine")]
+impl Dasher {
+    /// Returns a dasher starting `offset` into `pattern`, which must be a
+    /// pattern returned by [`StrokeStyle::dash_pattern()`].
+    pub(crate) fn new(pattern: Vec<f32>, offset: f32) -> Self {
+        let mut dasher = Self {
+            remaining: pattern[0],
+            pattern,
+            index: 0,
+        };
+        let total = dasher.pattern.iter().sum::<f32>();
+        let offset = offset.rem_euclid(total);
+        dasher.advance(if offset.is_finite() { offset } else { 0. });
+        dasher
+    }
+
+    /// Returns the dashes along the polyline through `points`. A dash that
+    /// continues around a corner is a single polyline, so that the corner can
+    /// be joined.
+    pub(crate) fn dashes(&mut self, points: &[Point<f32, Scaled>]) -> Vec<Vec<Point<f32, Scaled>>> {
+        let mut dashes = Vec::<Vec<Point<f32, Scaled>>>::new();
+        // Whether the last dash is still being drawn.
+        let mut dashing = false;
+        let mut steps = 0;
+        for line in points.windows(2) {
+            let (start, end) = (line[0], line[1]);
+            let length = (end.x - start.x).hypot(end.y - start.y);
+            if !length.is_finite() {
+                dashing = false;
+                continue;
+            }
+            let along = |distance: f32| {
+                let fraction = if length > 0. { distance / length } else { 0. };
+                Point::new(
+                    start.x + (end.x - start.x) * fraction,
+                    start.y + (end.y - start.y) * fraction,
+                )
+            };
+
+            let mut position = 0.;
+            while position < length {
+                let step = self.remaining.min(length - position);
+                let next = position + step;
+                steps += 1;
+                if next <= position || steps > MAX_DASH_STEPS {
+                    return dashes;
+                }
+
+                if self.index % 2 == 0 {
+                    match dashes.last_mut() {
+                        Some(dash) if dashing => dash.push(along(next)),
+                        _ => dashes.push(vec![along(position), along(next)]),
+                    }
+                    dashing = true;
+                }
+                position = next;
+                self.advance(step);
+                if self.index % 2 == 1 {
+                    dashing = false;
+                }
+            }
+        }
+        dashes
+    }
+
+    fn advance(&mut self, mut distance: f32) {
+        loop {
+            if distance < self.remaining {
+                self.remaining -= distance;
+                return;
+            }
+            distance -= self.remaining;
+            self.index = (self.index + 1) % self.pattern.len();
+            self.remaining = self.pattern[self.index];
+            if distance <= 0. && self.remaining > 0. {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn odd_patterns_are_repeated() {
+        assert_eq!(
+            StrokeStyle::dashed(vec![1., 2., 3.]).dash_pattern(),
+            Some(vec![1., 2., 3., 1., 2., 3.])
+        );
+        assert_eq!(
+            StrokeStyle::dashed(vec![4., 2.]).dash_pattern(),
+            Some(vec![4., 2.])
+        );
+    }
+
+    #[test]
+    fn invalid_patterns_are_solid() {
+        assert_eq!(StrokeStyle::default().dash_pattern(), None);
+        assert_eq!(StrokeStyle::dashed(vec![0., 0.]).dash_pattern(), None);
+        assert_eq!(StrokeStyle::dashed(vec![4., -1.]).dash_pattern(), None);
+        assert_eq!(StrokeStyle::dashed(vec![4., f32::NAN]).dash_pattern(), None);
+        assert_eq!(
+            StrokeStyle::dashed(vec![f32::MAX, f32::MAX]).dash_pattern(),
+            None
+        );
+    }
+
+    #[cfg(feature = "frontend-kludgine")]
+    mod dasher {
+        use super::*;
+
+        fn point(x: f32, y: f32) -> Point<f32, Scaled> {
+            Point::new(x, y)
+        }
+
+        fn coordinates(dashes: &[Vec<Point<f32, Scaled>>]) -> Vec<Vec<(f32, f32)>> {
+            dashes
+                .iter()
+                .map(|dash| dash.iter().map(|point| (point.x, point.y)).collect())
+                .collect()
+        }
+
+        #[test]
+        fn lines_are_split_into_dashes() {
+            let mut dasher = Dasher::new(vec![4., 2.], 0.);
+            assert_eq!(
+                coordinates(&dasher.dashes(&[point(0., 0.), point(14., 0.)])),
+                vec![
+                    vec![(0., 0.), (4., 0.)],
+                    vec![(6., 0.), (10., 0.)],
+                    vec![(12., 0.), (14., 0.)],
+                ]
+            );
+        }
+
+        #[test]
+        fn offsets_start_into_the_pattern() {
+            let mut dasher = Dasher::new(vec![4., 2.], 3.);
+            assert_eq!(
+                coordinates(&dasher.dashes(&[point(0., 0.), point(6., 0.)])),
+                vec![vec![(0., 0.), (1., 0.)], vec![(3., 0.), (6., 0.)]]
+            );
+            // Negative offsets wrap around the pattern.
+            let mut dasher = Dasher::new(vec![4., 2.], -1.);
+            assert_eq!(
+                coordinates(&dasher.dashes(&[point(0., 0.), point(6., 0.)])),
+                vec![vec![(1., 0.), (5., 0.)]]
+            );
+        }
+
+        #[test]
+        fn dashes_continue_around_corners() {
+            let mut dasher = Dasher::new(vec![6., 2.], 0.);
+            assert_eq!(
+                coordinates(&dasher.dashes(&[point(0., 0.), point(4., 0.), point(4., 6.)])),
+                vec![vec![(0., 0.), (4., 0.), (4., 2.)], vec![(4., 4.), (4., 6.)],]
+            );
+        }
+
+        #[test]
+        fn tiny_patterns_finish() {
+            let mut dasher = Dasher::new(vec![1e-6, 1e-6], 0.);
+            let dashes = dasher.dashes(&[point(0., 0.), point(1e6, 0.)]);
+            assert!(dashes.len() <= MAX_DASH_STEPS);
+
+            let mut dasher = Dasher::new(vec![1., 1.], f32::INFINITY);
+            assert!(dasher
+                .dashes(&[point(0., 0.), point(f32::INFINITY, 0.)])
+                .is_empty());
+        }
+    }
+}
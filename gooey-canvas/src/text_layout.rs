@@ -0,0 +1,308 @@
+use std::{cmp::Ordering, ops::Range};
+
+use gooey::{
+    core::{
+        figures::{Point, Rect, Size, SizedRect},
+        Scaled,
+    },
+    renderer::{Renderer, TextOptions},
+};
+
+/// The width of the rectangle returned by [`TextLayout::caret_rect()`].
+pub const CARET_WIDTH: f32 = 1.;
+
+/// A character of laid-out text.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Glyph<'a> {
+    /// The byte offset of this character within the text.
+    pub index: usize,
+    /// The character's text.
+    pub text: &'a str,
+    /// The distance from the start of the text to this character's origin.
+    pub offset: f32,
+    /// How far this character advances the origin of the next one.
+    pub advance: f32,
+}
+
+/// Returns the characters of `text` with their positions along its baseline,
+/// measured with `renderer`. Each character's advance is measured together
+/// with the character after it, so that positions include kerning while the
+/// number of measurements only grows linearly with the length of the text.
+pub(crate) fn glyphs<'a, R: Renderer>(
+    renderer: &R,
+    text: &'a str,
+    options: &TextOptions,
+) -> Vec<Glyph<'a>> {
+    measured_glyphs(text, |text| {
+        renderer.measure_text(text, options).width.get()
+    })
+}
+
+/// Returns the characters of `text` with their positions, using `measure` to
+/// find the width of a string.
+fn measured_glyphs(text: &str, measure: impl Fn(&str) -> f32) -> Vec<Glyph<'_>> {
+    let characters = text
+        .char_indices()
+        .map(|(index, character)| (index, &text[index..index + character.len_utf8()]))
+        .collect::<Vec<_>>();
+    let widths = characters
+        .iter()
+        .map(|(_, character)| measure(character))
+        .collect::<Vec<_>>();
+
+    let mut glyphs = Vec::with_capacity(characters.len());
+    let mut offset = 0.;
+    for (position, (index, character)) in characters.iter().enumerate() {
+        let advance = match characters.get(position + 1) {
+            Some((next_index, next)) => {
+                measure(&text[*index..next_index + next.len()]) - widths[position + 1]
+            }
+            None => widths[position],
+        };
+        glyphs.push(Glyph {
+            index: *index,
+            text: *character,
+            offset,
+            advance,
+        });
+        offset += advance;
+    }
+    glyphs
+}
+
+/// A line of text measured for hit-testing, caret placement and selection.
+/// Created with
+/// [`CanvasRenderer::layout_text()`](crate::CanvasRenderer::layout_text).
+///
+/// Positions are measured with the renderer that draws the text, so carets
+/// and selections line up with text drawn with the same options. Indexes are
+/// byte offsets into the text, and are moved back to the start of the
+/// character they fall within.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLayout {
+    text: String,
+    baseline_origin: Point<f32, Scaled>,
+    /// The byte offset and distance from the origin of each character
+    /// boundary, including the end of the text.
+    boundaries: Vec<(usize, f32)>,
+    ascent: f32,
+    descent: f32,
+}
+
+impl TextLayout {
+    /// Returns the layout of `text`, measured with `renderer`. `ascent` and
+    /// `descent` are the font's, so that carets are the same height whatever
+    /// characters they're next to.
+    pub(crate) fn new<R: Renderer>(
+        renderer: &R,
+        text: &str,
+        baseline_origin: Point<f32, Scaled>,
+        options: &TextOptions,
+        metrics: (f32, f32),
+    ) -> Self {
+        Self::measured(
+            text,
+            baseline_origin,
+            |text| renderer.measure_text(text, options).width.get(),
+            options.text_size.get(),
+            metrics,
+        )
+    }
+
+    /// Returns the layout of `text`, using `measure` to find the width of a
+    /// string. Carets are `text_size` tall if the font has no ascent or
+    /// descent.
+    fn measured(
+        text: &str,
+        baseline_origin: Point<f32, Scaled>,
+        measure: impl Fn(&str) -> f32,
+        text_size: f32,
+        (ascent, descent): (f32, f32),
+    ) -> Self {
+        let mut boundaries = measured_glyphs(text, &measure)
+            .into_iter()
+            .map(|glyph| (glyph.index, glyph.offset))
+            .collect::<Vec<_>>();
+        boundaries.push((text.len(), measure(text)));
+
+        let mut ascent = ascent.abs();
+        let descent = descent.abs();
+        if ascent + descent <= 0. {
+            ascent = text_size;
+        }
+        Self {
+            text: text.to_string(),
+            baseline_origin,
+            boundaries,
+            ascent,
+            descent,
+        }
+    }
+
+    /// Returns the text that was laid out.
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns where the text's baseline starts.
+    #[must_use]
+    pub const fn baseline_origin(&self) -> Point<f32, Scaled> {
+        self.baseline_origin
+    }
+
+    /// Returns the width of the text.
+    #[must_use]
+    pub fn width(&self) -> f32 {
+        self.boundaries.last().map_or(0., |(_, offset)| *offset)
+    }
+
+    /// Returns the index of the character boundary closest to `location`
+    /// horizontally. Locations before the text return 0, and locations after
+    /// it return the length of the text.
+    #[must_use]
+    pub fn index_at(&self, location: Point<f32, Scaled>) -> usize {
+        let x = location.x - self.baseline_origin.x;
+        self.boundaries
+            .iter()
+            .min_by(|(_, a), (_, b)| {
+                (a - x)
+                    .abs()
+                    .partial_cmp(&(b - x).abs())
+                    .unwrap_or(Ordering::Equal)
+            })
+            .map_or(0, |(index, _)| *index)
+    }
+
+    /// Returns the rectangle of a caret before the character at `index`. The
+    /// caret spans the ascent and descent of the font and is
+    /// [`CARET_WIDTH`] wide.
+    #[must_use]
+    pub fn caret_rect(&self, index: usize) -> Rect<f32, Scaled> {
+        let x = self.offset_of(index);
+        self.span(x - CARET_WIDTH / 2., CARET_WIDTH)
+    }
+
+    /// Returns the rectangle covering the characters in `range`.
+    #[must_use]
+    pub fn selection_rect(&self, range: Range<usize>) -> Rect<f32, Scaled> {
+        let start = self.offset_of(range.start.min(range.end));
+        let end = self.offset_of(range.start.max(range.end));
+        self.span(start, end - start)
+    }
+
+    /// Returns the distance from the origin to the boundary at or before
+    /// `index`.
+    fn offset_of(&self, index: usize) -> f32 {
+        let boundary = self
+            .boundaries
+            .partition_point(|(boundary, _)| *boundary <= index)
+            .max(1);
+        self.boundaries[boundary - 1].1
+    }
+
+    fn span(&self, x: f32, width: f32) -> Rect<f32, Scaled> {
+        Rect::from(SizedRect::new(
+            Point::new(
+                self.baseline_origin.x + x,
+                self.baseline_origin.y - self.ascent,
+            ),
+            Size::new(width, self.ascent + self.descent),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Measures 5 points per character, with "AV" kerned 2 points closer.
+    fn measure(text: &str) -> f32 {
+        text.chars().count() as f32 * 5. - text.matches("AV").count() as f32 * 2.
+    }
+
+    fn layout(text: &str) -> TextLayout {
+        TextLayout::measured(text, Point::new(10., 20.), measure, 12., (8., -2.))
+    }
+
+    fn bounds(rect: Rect<f32, Scaled>) -> (Point<f32, Scaled>, Size<f32, Scaled>) {
+        let rect = rect.as_sized();
+        (rect.origin, rect.size)
+    }
+
+    #[test]
+    fn glyphs_include_kerning() {
+        let glyphs = measured_glyphs("AVA", measure);
+        let positions = glyphs
+            .iter()
+            .map(|glyph| (glyph.index, glyph.text, glyph.offset, glyph.advance))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            positions,
+            vec![(0, "A", 0., 3.), (1, "V", 3., 5.), (2, "A", 8., 5.)]
+        );
+        assert!((layout("AVA").width() - measure("AVA")).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn locations_outside_the_text_are_clamped() {
+        let layout = layout("abc");
+        assert_eq!(layout.index_at(Point::new(-50., 20.)), 0);
+        assert_eq!(layout.index_at(Point::new(10., 20.)), 0);
+        assert_eq!(layout.index_at(Point::new(25., 20.)), 3);
+        assert_eq!(layout.index_at(Point::new(500., 20.)), 3);
+    }
+
+    #[test]
+    fn locations_round_to_the_nearest_boundary() {
+        let layout = layout("abc");
+        assert_eq!(layout.index_at(Point::new(17., 0.)), 1);
+        assert_eq!(layout.index_at(Point::new(18., 0.)), 2);
+        assert_eq!(layout.index_at(Point::new(12., 0.)), 0);
+        assert_eq!(layout.index_at(Point::new(13., 0.)), 1);
+    }
+
+    #[test]
+    fn multi_byte_characters() {
+        let layout = layout("a\u{e9}\u{65e5}b");
+        assert_eq!(layout.index_at(Point::new(20., 0.)), 3);
+        assert_eq!(layout.index_at(Point::new(25., 0.)), 6);
+        assert_eq!(layout.index_at(Point::new(30., 0.)), 7);
+        // Indexes within a character are moved back to its start.
+        assert_eq!(bounds(layout.caret_rect(2)), bounds(layout.caret_rect(1)));
+        assert_eq!(bounds(layout.caret_rect(5)), bounds(layout.caret_rect(3)));
+        assert_eq!(bounds(layout.caret_rect(100)), bounds(layout.caret_rect(7)));
+    }
+
+    #[test]
+    fn carets_span_the_font() {
+        let layout = layout("abc");
+        assert_eq!(
+            bounds(layout.caret_rect(1)),
+            (Point::new(14.5, 12.), Size::new(CARET_WIDTH, 10.))
+        );
+
+        let empty = TextLayout::measured("", Point::new(10., 20.), measure, 12., (0., 0.));
+        assert_eq!(
+            bounds(empty.caret_rect(0)),
+            (Point::new(9.5, 8.), Size::new(CARET_WIDTH, 12.))
+        );
+    }
+
+    #[test]
+    fn selections() {
+        let layout = layout("abcd");
+        assert_eq!(
+            bounds(layout.selection_rect(1..3)),
+            (Point::new(15., 12.), Size::new(10., 10.))
+        );
+        assert_eq!(
+            bounds(layout.selection_rect(3..1)),
+            bounds(layout.selection_rect(1..3))
+        );
+        assert_eq!(
+            bounds(layout.selection_rect(2..2)),
+            (Point::new(20., 12.), Size::new(0., 10.))
+        );
+    }
+}
@@ -0,0 +1,293 @@
+#[cfg(feature = "frontend-kludgine")]
+use std::collections::HashMap;
+use std::{
+    fmt::Display,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+};
+
+use gooey::core::{figures::Size, Pixels};
+
+use crate::CanvasImage;
+
+/// An image whose pixels are provided at runtime, such as a heatmap or a
+/// frame of video. Draw it with
+/// [`CanvasRenderer::draw_texture()`](crate::CanvasRenderer::draw_texture).
+///
+/// `Texture` is a handle: clones refer to the same pixels, so a texture can be
+/// updated in place while a [`Renderable`](crate::Renderable) draws it.
+/// Updating a texture doesn't redraw the canvases it's drawn on; send
+/// [`Command::Refresh`](crate::Command::Refresh) to show the new pixels.
+#[derive(Debug, Clone)]
+pub struct Texture {
+    data: Arc<Mutex<TextureData>>,
+}
+
+#[derive(Debug)]
+pub(crate) struct TextureData {
+    pub id: u64,
+    pub pixels: CanvasImage,
+    /// Incremented each time the pixels change, so that backends know when to
+    /// upload them again.
+    pub version: u64,
+    #[cfg(feature = "frontend-kludgine")]
+    pub rects: Option<(u64, Arc<Vec<PixelRect>>)>,
+}
+
+/// A rectangle of pixels with the same color.
+#[cfg(feature = "frontend-kludgine")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PixelRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+    pub rgba: [u8; 4],
+}
+
+/// An error creating or updating a [`Texture`].
+#[derive(Debug)]
+pub enum TextureError {
+    /// The buffer doesn't contain exactly four bytes for every pixel.
+    InvalidSize,
+    /// The encoded image couldn't be decoded.
+    #[cfg(feature = "image")]
+    Decode(image::ImageError),
+}
+
+impl Display for TextureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidSize => f.write_str("the buffer doesn't match the image size"),
+            #[cfg(feature = "image")]
+            Self::Decode(err) => write!(f, "error decoding image: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for TextureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::InvalidSize => None,
+            #[cfg(feature = "image")]
+            Self::Decode(err) => Some(err),
+        }
+    }
+}
+
+#[cfg(feature = "image")]
+impl From<image::ImageError> for TextureError {
+    fn from(err: image::ImageError) -> Self {
+        Self::Decode(err)
+    }
+}
+
+impl Texture {
+    /// Returns a texture of `size` containing `rgba`, which must contain four
+    /// bytes per pixel, row by row from the top.
+    pub fn from_rgba(size: Size<u32, Pixels>, rgba: Vec<u8>) -> Result<Self, TextureError> {
+        let pixels = CanvasImage::new(size, rgba).ok_or(TextureError::InvalidSize)?;
+        Ok(Self::from(pixels))
+    }
+
+    /// Returns a texture decoded from PNG or JPEG `bytes`. Requires the
+    /// `image` feature.
+    #[cfg(feature = "image")]
+    pub fn from_encoded(bytes: &[u8]) -> Result<Self, TextureError> {
+        decode(bytes).map(Self::from)
+    }
+
+    /// Returns the size of the texture.
+    #[must_use]
+    pub fn size(&self) -> Size<u32, Pixels> {
+        self.data().pixels.size()
+    }
+
+    /// Replaces the pixels of the texture with `rgba`, which must be the same
+    /// size as the current pixels.
+    pub fn update(&self, rgba: Vec<u8>) -> Result<(), TextureError> {
+        let size = self.size();
+        self.replace(size, rgba)
+    }
+
+    /// Replaces the texture with `rgba` pixels of `size`.
+    pub fn replace(&self, size: Size<u32, Pixels>, rgba: Vec<u8>) -> Result<(), TextureError> {
+        let pixels = CanvasImage::new(size, rgba).ok_or(TextureError::InvalidSize)?;
+        self.set_pixels(pixels);
+        Ok(())
+    }
+
+    /// Replaces the texture with an image decoded from PNG or JPEG `bytes`.
+    /// Requires the `image` feature.
+    #[cfg(feature = "image")]
+    pub fn replace_encoded(&self, bytes: &[u8]) -> Result<(), TextureError> {
+        self.set_pixels(decode(bytes)?);
+        Ok(())
+    }
+
+    fn set_pixels(&self, pixels: CanvasImage) {
+        let mut data = self.data();
+        data.pixels = pixels;
+        data.version += 1;
+    }
+
+    pub(crate) fn data(&self) -> MutexGuard<'_, TextureData> {
+        self.data.lock().unwrap()
+    }
+
+    /// Returns rectangles covering the texture's pixels, skipping fully
+    /// transparent pixels. Each row is split into runs of same-colored pixels,
+    /// and runs that exactly match a run in the row above extend its
+    /// rectangle downwards.
+    #[cfg(feature = "frontend-kludgine")]
+    pub(crate) fn pixel_rects(&self) -> Arc<Vec<PixelRect>> {
+        let mut data = self.data();
+        if let Some((version, rects)) = &data.rects {
+            if *version == data.version {
+                return rects.clone();
+            }
+        }
+
+        let rects = Arc::new(pixel_rects(&data.pixels));
+        data.rects = Some((data.version, rects.clone()));
+        rects
+    }
+}
+
+#[cfg(feature = "frontend-kludgine")]
+fn pixel_rects(pixels: &CanvasImage) -> Vec<PixelRect> {
+    let size = pixels.size();
+    let width = size.width as usize;
+    let rgba = pixels.rgba();
+    let mut rects = Vec::<PixelRect>::new();
+    // The rectangles reaching the previous row, by their left edge.
+    let mut open = HashMap::<u32, usize>::new();
+    for y in 0..size.height {
+        let row = &rgba[y as usize * width * 4..(y as usize + 1) * width * 4];
+        let mut runs = Vec::<PixelRect>::new();
+        for (x, pixel) in row.chunks_exact(4).enumerate() {
+            let pixel = [pixel[0], pixel[1], pixel[2], pixel[3]];
+            match runs.last_mut() {
+                Some(run) if run.rgba == pixel => run.width += 1,
+                _ => runs.push(PixelRect {
+                    x: x as u32,
+                    y,
+                    width: 1,
+                    height: 1,
+                    rgba: pixel,
+                }),
+            }
+        }
+
+        let mut reaching = HashMap::with_capacity(open.len());
+        for run in runs.into_iter().filter(|run| run.rgba[3] > 0) {
+            let above = open.get(&run.x).copied().filter(|&index| {
+                let rect = &rects[index];
+                rect.width == run.width && rect.rgba == run.rgba
+            });
+            let index = if let Some(index) = above {
+                rects[index].height += 1;
+                index
+            } else {
+                rects.push(run);
+                rects.len() - 1
+            };
+            reaching.insert(run.x, index);
+        }
+        open = reaching;
+    }
+    rects
+}
+
+impl From<CanvasImage> for Texture {
+    fn from(pixels: CanvasImage) -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Self {
+            data: Arc::new(Mutex::new(TextureData {
+                id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+                pixels,
+                version: 0,
+                #[cfg(feature = "frontend-kludgine")]
+                rects: None,
+            })),
+        }
+    }
+}
+
+impl Drop for TextureData {
+    fn drop(&mut self) {
+        #[cfg(feature = "frontend-browser")]
+        crate::browser::release_texture(self.id);
+    }
+}
+
+#[cfg(feature = "image")]
+fn decode(bytes: &[u8]) -> Result<CanvasImage, TextureError> {
+    let image = image::load_from_memory(bytes)?.to_rgba8();
+    let size = Size::new(image.width(), image.height());
+    CanvasImage::new(size, image.into_raw()).ok_or(TextureError::InvalidSize)
+}
+
+#[cfg(all(test, feature = "frontend-kludgine"))]
+mod tests {
+    use super::*;
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+    const CLEAR: [u8; 4] = [0, 0, 0, 0];
+
+    fn texture(width: u32, pixels: &[[u8; 4]]) -> Texture {
+        let height = pixels.len() as u32 / width;
+        Texture::from_rgba(Size::new(width, height), pixels.concat()).unwrap()
+    }
+
+    fn rect(x: u32, y: u32, width: u32, height: u32, rgba: [u8; 4]) -> PixelRect {
+        PixelRect {
+            x,
+            y,
+            width,
+            height,
+            rgba,
+        }
+    }
+
+    #[test]
+    fn matching_runs_merge_into_rectangles() {
+        let texture = texture(
+            3,
+            &[
+                RED, RED, BLUE, //
+                RED, RED, BLUE, //
+                RED, BLUE, BLUE, //
+            ],
+        );
+        assert_eq!(
+            texture.pixel_rects().as_slice(),
+            &[
+                rect(0, 0, 2, 2, RED),
+                rect(2, 0, 1, 2, BLUE),
+                rect(0, 2, 1, 1, RED),
+                rect(1, 2, 2, 1, BLUE),
+            ]
+        );
+    }
+
+    #[test]
+    fn transparent_pixels_are_skipped() {
+        let texture = texture(2, &[CLEAR, RED, CLEAR, CLEAR, CLEAR, RED]);
+        assert_eq!(
+            texture.pixel_rects().as_slice(),
+            &[rect(1, 0, 1, 1, RED), rect(1, 2, 1, 1, RED)]
+        );
+    }
+
+    #[test]
+    fn rectangles_follow_updates() {
+        let texture = texture(1, &[RED]);
+        assert_eq!(texture.pixel_rects().as_slice(), &[rect(0, 0, 1, 1, RED)]);
+        texture.update(BLUE.to_vec()).unwrap();
+        assert_eq!(texture.pixel_rects().as_slice(), &[rect(0, 0, 1, 1, BLUE)]);
+    }
+}
@@ -0,0 +1,153 @@
+use gooey::core::{
+    figures::{Point, Rect, Rectlike, Size, SizedRect, Vector},
+    Scaled,
+};
+
+/// A uniform scale followed by a translation.
+///
+/// [`CanvasRenderer`](crate::CanvasRenderer) uses this to map the coordinates
+/// passed to it onto the canvas: `canvas = point * scale + translation`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    /// The factor coordinates and sizes are multiplied by.
+    pub scale: f32,
+    /// The offset applied after scaling.
+    pub translation: Vector<f32, Scaled>,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Transform {
+    /// Returns a transform that leaves coordinates unchanged.
+    #[must_use]
+    pub fn identity() -> Self {
+        Self::new(1., Vector::default())
+    }
+
+    /// Returns a transform that scales by `scale` and then translates by
+    /// `translation`.
+    #[must_use]
+    pub const fn new(scale: f32, translation: Vector<f32, Scaled>) -> Self {
+        Self { scale, translation }
+    }
+
+    /// Returns true if this transform leaves coordinates unchanged.
+    #[must_use]
+    pub fn is_identity(&self) -> bool {
+        (self.scale - 1.).abs() < f32::EPSILON
+            && self.translation.x.abs() < f32::EPSILON
+            && self.translation.y.abs() < f32::EPSILON
+    }
+
+    /// Returns a transform that applies `self` followed by `outer`.
+    #[must_use]
+    pub fn then(&self, outer: &Self) -> Self {
+        Self::new(
+            self.scale * outer.scale,
+            Vector::new(
+                self.translation.x * outer.scale + outer.translation.x,
+                self.translation.y * outer.scale + outer.translation.y,
+            ),
+        )
+    }
+
+    /// Returns the transform that undoes `self`. A scale of zero collapses
+    /// every point onto one and can't be undone, so when the scale is zero or
+    /// not finite, only the translation is undone.
+    #[must_use]
+    pub fn inverse(&self) -> Self {
+        let scale = if self.scale.is_normal() {
+            self.scale
+        } else {
+            1.
+        };
+        Self::new(
+            1. / scale,
+            Vector::new(-self.translation.x / scale, -self.translation.y / scale),
+        )
+    }
+
+    /// Applies this transform to `point`.
+    #[must_use]
+    pub fn transform_point(&self, point: Point<f32, Scaled>) -> Point<f32, Scaled> {
+        Point::new(
+            point.x * self.scale + self.translation.x,
+            point.y * self.scale + self.translation.y,
+        )
+    }
+
+    /// Applies the scale of this transform to `size`.
+    #[must_use]
+    pub fn transform_size(&self, size: Size<f32, Scaled>) -> Size<f32, Scaled> {
+        Size::new(size.width * self.scale, size.height * self.scale)
+    }
+
+    /// Applies this transform to `rect`.
+    #[must_use]
+    pub fn transform_rect(&self, rect: Rect<f32, Scaled>) -> Rect<f32, Scaled> {
+        let rect = rect.as_sized();
+        Rect::from(SizedRect::new(
+            self.transform_point(rect.origin),
+            self.transform_size(rect.size),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inverse_undoes_the_transform() {
+        let transform = Transform::new(2., Vector::new(10., -4.));
+        let point = Point::new(3., 5.);
+        let transformed = transform.transform_point(point);
+        assert_eq!(transformed, Point::new(16., 6.));
+        assert_eq!(transform.inverse().transform_point(transformed), point);
+        assert!(transform.then(&transform.inverse()).is_identity());
+    }
+
+    #[test]
+    fn inverse_of_a_zero_scale_is_finite() {
+        for scale in [0., f32::NAN, f32::INFINITY] {
+            let inverse = Transform::new(scale, Vector::new(10., 20.)).inverse();
+            assert!(inverse.scale.is_finite());
+            assert_eq!(
+                inverse.transform_point(Point::new(10., 20.)),
+                Point::default()
+            );
+        }
+    }
+
+    #[test]
+    fn then_applies_self_first() {
+        let scale = Transform::new(2., Vector::default());
+        let translate = Transform::new(1., Vector::new(5., 5.));
+        let point = Point::new(1., 1.);
+        assert_eq!(
+            scale.then(&translate).transform_point(point),
+            Point::new(7., 7.)
+        );
+        assert_eq!(
+            translate.then(&scale).transform_point(point),
+            Point::new(12., 12.)
+        );
+    }
+
+    #[test]
+    fn rects_are_scaled_from_their_origin() {
+        let transform = Transform::new(2., Vector::new(1., 1.));
+        let rect = transform
+            .transform_rect(Rect::from(SizedRect::new(
+                Point::new(1., 2.),
+                Size::new(3., 4.),
+            )))
+            .as_sized();
+        assert_eq!(rect.origin, Point::new(3., 5.));
+        assert_eq!(rect.size, Size::new(6., 8.));
+    }
+}
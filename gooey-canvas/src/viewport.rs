@@ -0,0 +1,745 @@
+use std::{any::Any, time::Duration};
+
+use gooey::{
+    core::{
+        figures::{Point, Rect, Rectlike, Size, Vector},
+        Context, Scaled,
+    },
+    frontends::rasterizer::{ContentArea, ContentSize},
+};
+
+use crate::{
+    AccessibilityTree, Canvas, CanvasRenderer, DragEvent, EventStatus, GestureEvent, GestureKind,
+    PointerButton, PointerEvent, PointerEventKind, Renderable, Transform,
+};
+
+/// Wraps a [`Renderable`], allowing its contents to be panned by dragging and
+/// zoomed with the scroll wheel, a trackpad pinch or a two-finger
+/// [pinch gesture](crate::GestureKind::Pinch).
+///
+/// Kludgine doesn't report the scroll wheel, so there the view is zoomed by
+/// sending [`ViewportCommand::ZoomTo`].
+///
+/// The wrapped renderable draws in "world" coordinates, and receives pointer,
+/// gesture and drag events in world coordinates. Events it
+/// [ignores](EventStatus::Ignored) are used to pan and zoom the viewport.
+///
+/// Once a viewport is given to a [`Canvas`], it can be controlled by sending
+/// [`ViewportCommand`]s with [`Command::message()`](crate::Command::message).
+/// All other messages are forwarded to the wrapped renderable.
+#[derive(Debug)]
+pub struct Viewport<R> {
+    content: R,
+    transform: Transform,
+    transition: Option<Transition>,
+    transition_duration: Duration,
+    min_zoom: f32,
+    max_zoom: f32,
+    pan_button: Option<PointerButton>,
+    content_bounds: Option<Rect<f32, Scaled>>,
+    size: Option<Size<f32, Scaled>>,
+    /// Where the canvas is drawn, in the renderer's coordinates.
+    origin: Option<Point<f32, Scaled>>,
+    pending_fit: Option<Rect<f32, Scaled>>,
+    drag: Option<Drag>,
+}
+
+/// A message that controls a [`Viewport`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ViewportCommand {
+    /// Moves the view by an offset, in canvas coordinates.
+    PanBy(Vector<f32, Scaled>),
+    /// Zooms to `zoom`, keeping the world location under `anchor` in place.
+    /// `anchor` is in canvas coordinates, and the center of the canvas is
+    /// used when `None`.
+    ZoomTo {
+        /// The new zoom level.
+        zoom: f32,
+        /// The canvas location that stays in place.
+        anchor: Option<Point<f32, Scaled>>,
+    },
+    /// Zooms and pans so that the world rectangle fills the canvas.
+    FitTo(Rect<f32, Scaled>),
+    /// Fits the bounds set by [`Viewport::with_content_bounds()`] or
+    /// [`Viewport::set_content_bounds()`].
+    FitContent,
+    /// Resets to a zoom of 1 with the world origin at the top-left of the
+    /// canvas.
+    Reset,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Transition {
+    from: Transform,
+    to: Transform,
+    elapsed: Duration,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Drag {
+    pointer_id: u32,
+    button: PointerButton,
+    last_location: Point<f32, Scaled>,
+}
+
+impl<R: Renderable> Viewport<R> {
+    /// Returns a new viewport showing `content` with a zoom of 1.
+    pub fn new(content: R) -> Self {
+        Self {
+            content,
+            transform: Transform::identity(),
+            transition: None,
+            transition_duration: Duration::from_millis(250),
+            min_zoom: 0.1,
+            max_zoom: 10.,
+            pan_button: Some(PointerButton::Primary),
+            content_bounds: None,
+            size: None,
+            origin: None,
+            pending_fit: None,
+            drag: None,
+        }
+    }
+
+    /// Limits the zoom level to the range `min..=max`. The defaults are `0.1`
+    /// and `10`.
+    #[must_use]
+    pub fn with_zoom_limits(mut self, min: f32, max: f32) -> Self {
+        self.min_zoom = min;
+        self.max_zoom = max;
+        self.transform = self.clamped(self.transform, None);
+        self
+    }
+
+    /// Sets the button that pans the view when dragged, or disables panning by
+    /// dragging when `None`. The default is [`PointerButton::Primary`].
+    #[must_use]
+    pub fn with_pan_button(mut self, button: Option<PointerButton>) -> Self {
+        self.pan_button = button;
+        self
+    }
+
+    /// Sets the world bounds used by [`ViewportCommand::FitContent`].
+    #[must_use]
+    pub fn with_content_bounds(mut self, bounds: Rect<f32, Scaled>) -> Self {
+        self.content_bounds = Some(bounds);
+        self
+    }
+
+    /// Sets how long animated transitions take. The default is 250
+    /// milliseconds.
+    #[must_use]
+    pub fn with_transition_duration(mut self, duration: Duration) -> Self {
+        self.transition_duration = duration;
+        self
+    }
+
+    /// Returns the wrapped renderable.
+    pub const fn content(&self) -> &R {
+        &self.content
+    }
+
+    /// Returns the wrapped renderable.
+    pub fn content_mut(&mut self) -> &mut R {
+        &mut self.content
+    }
+
+    /// Sets the world bounds used by [`ViewportCommand::FitContent`].
+    pub fn set_content_bounds(&mut self, bounds: Rect<f32, Scaled>) {
+        self.content_bounds = Some(bounds);
+    }
+
+    /// Returns the current zoom level.
+    #[must_use]
+    pub const fn zoom(&self) -> f32 {
+        self.transform.scale
+    }
+
+    /// Returns the transform from world coordinates to canvas coordinates.
+    #[must_use]
+    pub const fn transform(&self) -> Transform {
+        self.transform
+    }
+
+    /// Converts `point` from world coordinates to canvas coordinates.
+    #[must_use]
+    pub fn world_to_screen(&self, point: Point<f32, Scaled>) -> Point<f32, Scaled> {
+        self.transform.transform_point(point)
+    }
+
+    /// Converts `point` from canvas coordinates to world coordinates.
+    #[must_use]
+    pub fn screen_to_world(&self, point: Point<f32, Scaled>) -> Point<f32, Scaled> {
+        self.transform.inverse().transform_point(point)
+    }
+
+    /// Moves the view by `delta`, in canvas coordinates.
+    pub fn pan_by(&mut self, delta: Vector<f32, Scaled>) {
+        self.transition = None;
+        self.transform = Transform::new(
+            self.transform.scale,
+            Vector::new(
+                self.transform.translation.x + delta.x,
+                self.transform.translation.y + delta.y,
+            ),
+        );
+    }
+
+    /// Zooms to `zoom`, keeping the world location under `anchor` in place.
+    /// `anchor` is in canvas coordinates.
+    pub fn zoom_around(&mut self, zoom: f32, anchor: Point<f32, Scaled>, animated: bool) {
+        let target = self.clamped(self.target(), Some(zoom));
+        let world_anchor = self.target().inverse().transform_point(anchor);
+        let target = Transform::new(
+            target.scale,
+            Vector::new(
+                anchor.x - world_anchor.x * target.scale,
+                anchor.y - world_anchor.y * target.scale,
+            ),
+        );
+        self.move_to(target, animated);
+    }
+
+    /// Zooms and pans so that `bounds`, in world coordinates, fills the
+    /// canvas. If the canvas hasn't been drawn yet, this happens once it is.
+    pub fn fit_to(&mut self, bounds: Rect<f32, Scaled>, animated: bool) {
+        let (size, origin) = match (self.size, self.origin) {
+            (Some(size), Some(origin)) => (size, origin),
+            _ => {
+                self.pending_fit = Some(bounds);
+                return;
+            }
+        };
+        let bounds = bounds.as_sized();
+        if bounds.size.width <= 0. || bounds.size.height <= 0. {
+            return;
+        }
+
+        let zoom = (size.width / bounds.size.width).min(size.height / bounds.size.height);
+        let target = self.clamped(self.transform, Some(zoom));
+        let center = Point::new(
+            bounds.origin.x + bounds.size.width / 2.,
+            bounds.origin.y + bounds.size.height / 2.,
+        );
+        let target = Transform::new(
+            target.scale,
+            Vector::new(
+                origin.x + size.width / 2. - center.x * target.scale,
+                origin.y + size.height / 2. - center.y * target.scale,
+            ),
+        );
+        self.move_to(target, animated);
+    }
+
+    fn apply(&mut self, command: ViewportCommand) {
+        match command {
+            ViewportCommand::PanBy(delta) => self.pan_by(delta),
+            ViewportCommand::ZoomTo { zoom, anchor } => {
+                let anchor = anchor.unwrap_or_else(|| {
+                    let size = self.size.unwrap_or_default();
+                    let origin = self.origin.unwrap_or_default();
+                    Point::new(origin.x + size.width / 2., origin.y + size.height / 2.)
+                });
+                self.zoom_around(zoom, anchor, true);
+            }
+            ViewportCommand::FitTo(bounds) => self.fit_to(bounds, true),
+            ViewportCommand::FitContent => {
+                if let Some(bounds) = self.content_bounds {
+                    self.fit_to(bounds, true);
+                }
+            }
+            ViewportCommand::Reset => {
+                let origin = self.origin.unwrap_or_default();
+                self.move_to(Transform::new(1., Vector::new(origin.x, origin.y)), true);
+            }
+        }
+    }
+
+    /// Returns the transform the viewport is moving towards.
+    fn target(&self) -> Transform {
+        self.transition
+            .map_or(self.transform, |transition| transition.to)
+    }
+
+    fn move_to(&mut self, target: Transform, animated: bool) {
+        if animated && !self.transition_duration.is_zero() {
+            self.transition = Some(Transition {
+                from: self.transform,
+                to: target,
+                elapsed: Duration::default(),
+            });
+        } else {
+            self.transition = None;
+            self.transform = target;
+        }
+    }
+
+    /// Returns `transform` with its scale, or `zoom` if provided, clamped to
+    /// the zoom limits.
+    fn clamped(&self, transform: Transform, zoom: Option<f32>) -> Transform {
+        let zoom = zoom
+            .unwrap_or(transform.scale)
+            .max(self.min_zoom)
+            .min(self.max_zoom);
+        Transform::new(zoom, transform.translation)
+    }
+
+    /// Advances the active transition. Returns true if it is still running.
+    fn animate(&mut self, elapsed: Duration) -> bool {
+        if let Some(transition) = &mut self.transition {
+            transition.elapsed += elapsed;
+            let progress =
+                (transition.elapsed.as_secs_f32() / self.transition_duration.as_secs_f32()).min(1.);
+            // Ease out, so that the transition decelerates into place.
+            let t = 1. - (1. - progress).powi(3);
+            let (from, to) = (transition.from, transition.to);
+            self.transform = Transform::new(
+                from.scale + (to.scale - from.scale) * t,
+                Vector::new(
+                    from.translation.x + (to.translation.x - from.translation.x) * t,
+                    from.translation.y + (to.translation.y - from.translation.y) * t,
+                ),
+            );
+            if progress >= 1. {
+                self.transition = None;
+            }
+        }
+        self.transition.is_some()
+    }
+
+    /// Returns `location`, relative to the canvas, in the coordinates the
+    /// canvas is drawn in. Kludgine draws canvases in window coordinates.
+    fn drawn_location(&self, location: Point<f32, Scaled>) -> Point<f32, Scaled> {
+        let origin = self.origin.unwrap_or_default();
+        Point::new(location.x + origin.x, location.y + origin.y)
+    }
+
+    fn handle_pointer(&mut self, event: &PointerEvent) -> EventStatus {
+        match event.kind {
+            PointerEventKind::Down(button)
+                if self.drag.is_none() && Some(button) == self.pan_button =>
+            {
+                self.drag = Some(Drag {
+                    pointer_id: event.pointer_id,
+                    button,
+                    last_location: event.location,
+                });
+                EventStatus::Processed
+            }
+            PointerEventKind::Move => {
+                // Other fingers moving while one pans are left to the pinch
+                // gesture.
+                if let Some(drag) = self
+                    .drag
+                    .as_mut()
+                    .filter(|drag| drag.pointer_id == event.pointer_id)
+                {
+                    let delta = Vector::new(
+                        event.location.x - drag.last_location.x,
+                        event.location.y - drag.last_location.y,
+                    );
+                    drag.last_location = event.location;
+                    self.pan_by(delta);
+                    EventStatus::Processed
+                } else {
+                    EventStatus::Ignored
+                }
+            }
+            PointerEventKind::Up(button)
+                if self.drag.map_or(false, |drag| {
+                    drag.pointer_id == event.pointer_id && drag.button == button
+                }) =>
+            {
+                self.drag = None;
+                EventStatus::Processed
+            }
+            PointerEventKind::Cancel
+                if self
+                    .drag
+                    .map_or(false, |drag| drag.pointer_id == event.pointer_id) =>
+            {
+                self.drag = None;
+                EventStatus::Processed
+            }
+            PointerEventKind::Wheel(delta) if delta.y.abs() > f32::EPSILON => {
+                let zoom = self.target().scale * (-delta.y / 500.).exp();
+                self.zoom_around(zoom, event.location, false);
+                EventStatus::Processed
+            }
+            _ => EventStatus::Ignored,
+        }
+    }
+}
+
+impl<R: Renderable> Renderable for Viewport<R> {
+    fn initialize(&mut self, context: &Context<Canvas>) {
+        self.content.initialize(context);
+    }
+
+    fn resized(&mut self, size: Size<f32, Scaled>) {
+        self.size = Some(size);
+        self.content.resized(size);
+    }
+
+    fn update(&mut self, elapsed: Duration) -> bool {
+        let animating = self.animate(elapsed);
+        self.content.update(elapsed) || animating
+    }
+
+    fn receive(&mut self, message: Box<dyn Any + Send + Sync>) {
+        match message.downcast::<ViewportCommand>() {
+            Ok(command) => self.apply(*command),
+            Err(message) => self.content.receive(message),
+        }
+    }
+
+    fn pointer(&mut self, event: &PointerEvent) -> EventStatus {
+        let event = PointerEvent {
+            location: self.drawn_location(event.location),
+            ..*event
+        };
+        // While panning, the content doesn't receive the drag.
+        if self.drag.is_none() {
+            let world_event = event.transformed(&self.transform.inverse());
+            if self.content.pointer(&world_event) == EventStatus::Processed {
+                return EventStatus::Processed;
+            }
+        }
+        self.handle_pointer(&event)
+    }
+
+    fn gesture(&mut self, event: &GestureEvent) -> EventStatus {
+        let event = GestureEvent {
+            location: self.drawn_location(event.location),
+            ..*event
+        };
+        let inverse = self.transform.inverse();
+        let world_event = GestureEvent {
+            kind: match event.kind {
+                GestureKind::Pan(delta) => GestureKind::Pan(Vector::new(
+                    delta.x * inverse.scale,
+                    delta.y * inverse.scale,
+                )),
+                other => other,
+            },
+            location: inverse.transform_point(event.location),
+        };
+        if self.content.gesture(&world_event) == EventStatus::Processed {
+            return EventStatus::Processed;
+        }
+
+        match event.kind {
+            GestureKind::Pinch(factor) => {
+                self.zoom_around(self.target().scale * factor, event.location, false);
+                EventStatus::Processed
+            }
+            _ => EventStatus::Ignored,
+        }
+    }
+
+    fn drag(&mut self, event: &DragEvent) -> EventStatus {
+        self.content.drag(&DragEvent {
+            kind: event.kind.clone(),
+            location: self
+                .transform
+                .inverse()
+                .transform_point(self.drawn_location(event.location)),
+        })
+    }
+
+    fn render(&mut self, renderer: CanvasRenderer, content_area: &ContentArea) {
+        if self.origin.is_none() {
+            // Until the canvas is first drawn, the world origin can't be
+            // placed at its top-left corner.
+            self.transform = Transform::new(
+                self.transform.scale,
+                Vector::new(
+                    self.transform.translation.x + content_area.location.x,
+                    self.transform.translation.y + content_area.location.y,
+                ),
+            );
+        }
+        self.origin = Some(content_area.location);
+        if let Some(bounds) = self.pending_fit.take() {
+            self.fit_to(bounds, false);
+        }
+
+        let visible = self
+            .transform
+            .inverse()
+            .transform_rect(content_area.bounds())
+            .as_sized();
+        self.content.render(
+            renderer.transformed(&self.transform),
+            &ContentArea {
+                location: visible.origin,
+                size: ContentSize {
+                    content: visible.size,
+                    ..ContentSize::default()
+                },
+            },
+        );
+    }
+
+    fn accessibility(&self) -> Option<AccessibilityTree> {
+        // Regions are described in world coordinates, and reported relative
+        // to the canvas.
+        let origin = self.origin.unwrap_or_default();
+        let to_canvas = Transform::new(
+            self.transform.scale,
+            Vector::new(
+                self.transform.translation.x - origin.x,
+                self.transform.translation.y - origin.y,
+            ),
+        );
+        let mut tree = self.content.accessibility()?;
+        for region in &mut tree.regions {
+            region.bounds = to_canvas.transform_rect(region.bounds);
+        }
+        Some(tree)
+    }
+
+    fn teardown(&mut self) {
+        self.content.teardown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gooey::core::figures::SizedRect;
+
+    use super::*;
+    use crate::{DragEventKind, DragPayload, Modifiers, PointerDevice};
+
+    #[derive(Debug)]
+    struct Empty;
+
+    impl Renderable for Empty {
+        fn render(&mut self, _renderer: CanvasRenderer, _content_area: &ContentArea) {}
+    }
+
+    /// Records the events it receives, without handling them.
+    #[derive(Debug, Default)]
+    struct Recorder {
+        pointers: Vec<Point<f32, Scaled>>,
+        drags: Vec<Point<f32, Scaled>>,
+    }
+
+    impl Renderable for Recorder {
+        fn pointer(&mut self, event: &PointerEvent) -> EventStatus {
+            self.pointers.push(event.location);
+            EventStatus::Ignored
+        }
+
+        fn drag(&mut self, event: &DragEvent) -> EventStatus {
+            self.drags.push(event.location);
+            EventStatus::Processed
+        }
+
+        fn render(&mut self, _renderer: CanvasRenderer, _content_area: &ContentArea) {}
+
+        fn accessibility(&self) -> Option<AccessibilityTree> {
+            Some(AccessibilityTree::new("map").with_region(
+                "pin",
+                Rect::from(SizedRect::new(Point::new(5., 5.), Size::new(10., 20.))),
+            ))
+        }
+    }
+
+    fn drag(kind: DragEventKind, x: f32, y: f32) -> DragEvent {
+        DragEvent {
+            kind,
+            location: Point::new(x, y),
+        }
+    }
+
+    fn pointer(kind: PointerEventKind, x: f32, y: f32) -> PointerEvent {
+        finger(0, kind, x, y)
+    }
+
+    fn finger(pointer_id: u32, kind: PointerEventKind, x: f32, y: f32) -> PointerEvent {
+        PointerEvent {
+            kind,
+            location: Point::new(x, y),
+            pointer_id,
+            device: PointerDevice::Mouse,
+            modifiers: Modifiers::default(),
+        }
+    }
+
+    fn assert_near(a: Point<f32, Scaled>, b: Point<f32, Scaled>) {
+        assert!(
+            (a.x - b.x).abs() < 0.001 && (a.y - b.y).abs() < 0.001,
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn screen_and_world_round_trip() {
+        let mut viewport = Viewport::new(Empty);
+        viewport.pan_by(Vector::new(30., 40.));
+        viewport.zoom_around(2., Point::new(10., 10.), false);
+        let world = Point::new(7., -3.);
+        assert_near(
+            viewport.screen_to_world(viewport.world_to_screen(world)),
+            world,
+        );
+    }
+
+    #[test]
+    fn zooming_keeps_the_anchor_in_place() {
+        let mut viewport = Viewport::new(Empty);
+        viewport.pan_by(Vector::new(15., 25.));
+        let anchor = Point::new(100., 50.);
+        let world_anchor = viewport.screen_to_world(anchor);
+        viewport.zoom_around(4., anchor, false);
+        assert!((viewport.zoom() - 4.).abs() < f32::EPSILON);
+        assert_near(viewport.world_to_screen(world_anchor), anchor);
+    }
+
+    #[test]
+    fn zoom_is_clamped_to_the_limits() {
+        let mut viewport = Viewport::new(Empty).with_zoom_limits(0.5, 2.);
+        viewport.zoom_around(100., Point::default(), false);
+        assert!((viewport.zoom() - 2.).abs() < f32::EPSILON);
+        viewport.zoom_around(0., Point::default(), false);
+        assert!((viewport.zoom() - 0.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn dragging_pans_and_the_wheel_zooms() {
+        let mut viewport = Viewport::new(Empty);
+        viewport.pointer(&pointer(
+            PointerEventKind::Down(PointerButton::Primary),
+            10.,
+            10.,
+        ));
+        viewport.pointer(&pointer(PointerEventKind::Move, 30., 5.));
+        viewport.pointer(&pointer(
+            PointerEventKind::Up(PointerButton::Primary),
+            30.,
+            5.,
+        ));
+        assert_eq!(viewport.transform().translation, Vector::new(20., -5.));
+
+        let anchor = Point::new(50., 50.);
+        let world_anchor = viewport.screen_to_world(anchor);
+        viewport.pointer(&pointer(
+            PointerEventKind::Wheel(Vector::new(0., -100.)),
+            anchor.x,
+            anchor.y,
+        ));
+        assert!(viewport.zoom() > 1.);
+        assert_near(viewport.world_to_screen(world_anchor), anchor);
+    }
+
+    #[test]
+    fn drags_are_forwarded_in_world_coordinates() {
+        let mut viewport = Viewport::new(Recorder::default());
+        viewport.pan_by(Vector::new(20., 10.));
+        viewport.zoom_around(2., Point::new(20., 10.), false);
+        let status = viewport.drag(&drag(
+            DragEventKind::Drop(DragPayload::Text(String::from("pin"))),
+            60.,
+            30.,
+        ));
+        assert_eq!(status, EventStatus::Processed);
+        assert_eq!(viewport.content().drags, vec![Point::new(20., 10.)]);
+    }
+
+    #[test]
+    fn accessible_regions_follow_the_view() {
+        let mut viewport = Viewport::new(Recorder::default());
+        viewport.origin = Some(Point::new(100., 50.));
+        viewport.transform = Transform::new(2., Vector::new(110., 40.));
+        let tree = viewport.accessibility().unwrap();
+        assert_eq!(tree.label, "map");
+        let bounds = tree.regions[0].bounds.as_sized();
+        assert_eq!(bounds.origin, Point::new(20., 0.));
+        assert_eq!(bounds.size, Size::new(20., 40.));
+    }
+
+    #[test]
+    fn fitting_waits_for_the_canvas_size() {
+        let mut viewport = Viewport::new(Empty).with_transition_duration(Duration::default());
+        let bounds = Rect::from(SizedRect::new(Point::new(0., 0.), Size::new(50., 25.)));
+        viewport.fit_to(bounds, false);
+        assert!(viewport.transform().is_identity());
+
+        viewport.resized(Size::new(200., 200.));
+        viewport.origin = Some(Point::new(10., 20.));
+        viewport.fit_to(bounds, false);
+        assert!((viewport.zoom() - 4.).abs() < f32::EPSILON);
+        // The bounds are centered in the canvas, which is drawn at its origin.
+        assert_near(
+            viewport.world_to_screen(Point::new(25., 12.5)),
+            Point::new(110., 120.),
+        );
+    }
+
+    #[test]
+    fn cancelling_the_pointer_stops_panning() {
+        let mut viewport = Viewport::new(Empty);
+        viewport.pointer(&pointer(
+            PointerEventKind::Down(PointerButton::Primary),
+            10.,
+            10.,
+        ));
+        viewport.pointer(&pointer(PointerEventKind::Cancel, 10., 10.));
+        assert_eq!(
+            viewport.pointer(&pointer(PointerEventKind::Move, 30., 30.)),
+            EventStatus::Ignored
+        );
+        assert!(viewport.transform().is_identity());
+    }
+
+    #[test]
+    fn only_the_first_finger_pans() {
+        let mut viewport = Viewport::new(Empty);
+        let down = PointerEventKind::Down(PointerButton::Primary);
+        viewport.pointer(&finger(0, down, 10., 10.));
+        viewport.pointer(&finger(1, down, 50., 10.));
+        viewport.pointer(&finger(1, PointerEventKind::Move, 70., 10.));
+        assert!(viewport.transform().is_identity());
+        viewport.pointer(&finger(0, PointerEventKind::Move, 15., 10.));
+        assert_eq!(viewport.transform().translation, Vector::new(5., 0.));
+    }
+
+    #[test]
+    fn events_are_relative_to_the_canvas() {
+        // Kludgine draws the canvas at its location in the window, and
+        // reports pointers relative to the canvas.
+        let mut viewport = Viewport::new(Recorder::default());
+        viewport.origin = Some(Point::new(100., 50.));
+        viewport.transform = Transform::new(2., Vector::new(100., 50.));
+        viewport.pointer(&pointer(PointerEventKind::Move, 20., 10.));
+        assert_eq!(viewport.content().pointers, vec![Point::new(10., 5.)]);
+
+        let world_anchor = viewport.screen_to_world(Point::new(120., 60.));
+        viewport.pointer(&pointer(
+            PointerEventKind::Wheel(Vector::new(0., -100.)),
+            20.,
+            10.,
+        ));
+        assert_near(
+            viewport.world_to_screen(world_anchor),
+            Point::new(120., 60.),
+        );
+    }
+
+    #[test]
+    fn pinching_zooms_around_the_fingers() {
+        let mut viewport = Viewport::new(Empty);
+        let anchor = Point::new(40., 60.);
+        let world_anchor = viewport.screen_to_world(anchor);
+        let status = viewport.gesture(&GestureEvent {
+            kind: GestureKind::Pinch(2.),
+            location: anchor,
+        });
+        assert_eq!(status, EventStatus::Processed);
+        assert!((viewport.zoom() - 2.).abs() < f32::EPSILON);
+        assert_near(viewport.world_to_screen(world_anchor), anchor);
+    }
+}
@@ -0,0 +1,184 @@
+//! Replays the conformance scenes through the browser backend and compares
+//! them against the goldens generated by the native conformance example. Run
+//! with `cargo xtask test-browser-conformance`.
+#![cfg(all(target_arch = "wasm32", feature = "frontend-browser"))]
+
+#[path = "../examples/harness/mod.rs"]
+mod harness;
+#[path = "../examples/conformance/scenes.rs"]
+mod scenes;
+
+use gooey::{
+    core::{
+        figures::{Point, Rect, Size, SizedRect},
+        Callback, Context,
+    },
+    frontends::rasterizer::ContentArea,
+    App,
+};
+use gooey_canvas::{
+    AccessibilityTree, AppExt, Canvas, CanvasImage, CanvasRenderer, CanvasRole, Command, Renderable,
+};
+use harness::Tolerance;
+use image::{imageops, ImageFormat, RgbaImage};
+use scenes::{SceneCanvas, MISMATCH_TOLERANCE, SCENES};
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+macro_rules! golden {
+    ($name:literal) => {
+        &include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/examples/snapshots/conformance/",
+            $name,
+            ".png"
+        ))[..]
+    };
+}
+
+/// Returns the encoded golden image for the scene named `name`.
+fn golden(name: &str) -> Option<&'static [u8]> {
+    match name {
+        "strokes" => Some(golden!("strokes")),
+        "clipping" => Some(golden!("clipping")),
+        "text-baselines" => Some(golden!("text-baselines")),
+        "nested-clips" => Some(golden!("nested-clips")),
+        "shaped-clips" => Some(golden!("shaped-clips")),
+        "textures" => Some(golden!("textures")),
+        "dashes" => Some(golden!("dashes")),
+        "pixel-snapping" => Some(golden!("pixel-snapping")),
+        _ => None,
+    }
+}
+
+/// The browser frontend mounts into the `gooey` element, as it does in
+/// `examples/browser/index.html`.
+fn prepare_document() {
+    let body = web_sys::window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .body()
+        .unwrap();
+    body.set_id("gooey");
+    body.set_attribute("style", "margin: 0; width: 320px; height: 240px")
+        .unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn conformance() {
+    prepare_document();
+
+    let tolerance = Tolerance {
+        mismatched: MISMATCH_TOLERANCE,
+        ..Tolerance::default()
+    };
+    let mut failures = Vec::new();
+    // Assets aren't served by the test runner.
+    for scene in SCENES.iter().filter(|scene| !scene.uses_assets) {
+        let (sender, receiver) = flume::bounded(1);
+        App::from_root(move |_storage| {
+            let sender = sender.clone();
+            Canvas::new(SceneCanvas::new(scene).on_initialize(move |context| {
+                context.send_command(Command::Capture(Callback::new(
+                    move |image: Option<CanvasImage>| {
+                        drop(sender.send(image));
+                    },
+                )));
+            }))
+        })
+        .with_canvas()
+        .run();
+
+        let image = receiver
+            .recv_async()
+            .await
+            .unwrap()
+            .expect("the browser frontend supports capturing");
+        let golden = golden(scene.name).expect("missing golden");
+        let golden = image::load_from_memory_with_format(golden, ImageFormat::Png)
+            .unwrap()
+            .to_rgba8();
+        let size = image.size();
+        let actual = RgbaImage::from_raw(size.width, size.height, image.into_rgba()).unwrap();
+        // The canvas fills its container, which may be larger than the scene.
+        let actual = imageops::crop_imm(&actual, 0, 0, golden.width(), golden.height()).to_image();
+        if actual.dimensions() != golden.dimensions() {
+            failures.push(format!(
+                "{}: the canvas is smaller than the golden",
+                scene.name
+            ));
+            continue;
+        }
+
+        let comparison = harness::compare(&actual, &golden, tolerance);
+        if !comparison.passed() {
+            failures.push(format!(
+                "{}: {} pixels differ from the golden (at most {} allowed)",
+                scene.name, comparison.mismatched, comparison.allowed
+            ));
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+/// A scene that describes itself to assistive technology.
+struct DescribedScene(SceneCanvas);
+
+impl Renderable for DescribedScene {
+    fn initialize(&mut self, context: &Context<Canvas>) {
+        self.0.initialize(context);
+    }
+
+    fn render(&mut self, renderer: CanvasRenderer, content_area: &ContentArea) {
+        self.0.render(renderer, content_area);
+    }
+
+    fn accessibility(&self) -> Option<AccessibilityTree> {
+        Some(
+            AccessibilityTree::new("Stroke widths")
+                .with_role(CanvasRole::Figure)
+                .with_region(
+                    "Thinnest stroke",
+                    Rect::from(SizedRect::new(Point::new(16., 16.), Size::new(60., 60.))),
+                ),
+        )
+    }
+}
+
+#[wasm_bindgen_test]
+async fn accessibility_fallback() {
+    prepare_document();
+
+    let (sender, receiver) = flume::bounded(1);
+    App::from_root(move |_storage| {
+        let sender = sender.clone();
+        Canvas::new(DescribedScene(SceneCanvas::new(&SCENES[0]).on_initialize(
+            move |context| {
+                context.send_command(Command::Capture(Callback::new(
+                    move |_: Option<CanvasImage>| {
+                        drop(sender.send(()));
+                    },
+                )));
+            },
+        )))
+    })
+    .with_canvas()
+    .run();
+    receiver.recv_async().await.unwrap();
+
+    let canvas = web_sys::window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .query_selector("canvas[aria-label='Stroke widths']")
+        .unwrap()
+        .expect("the canvas is labeled");
+    assert_eq!(canvas.get_attribute("role").as_deref(), Some("figure"));
+    let region = canvas
+        .query_selector("button")
+        .unwrap()
+        .expect("regions are emitted as fallback content");
+    assert_eq!(region.text_content().as_deref(), Some("Thinnest stroke"));
+}
@@ -13,6 +13,7 @@ enum Args {
         install_dependencies: bool,
     },
     GenerateExampleSnapshots,
+    TestBrowserConformance,
 }
 
 fn main() -> anyhow::Result<()> {
@@ -25,6 +26,7 @@ fn main() -> anyhow::Result<()> {
             install_dependencies,
         } => CodeCoverage::<CodeCoverageConfig>::execute(install_dependencies)?,
         Args::GenerateExampleSnapshots => generate_example_snapshots()?,
+        Args::TestBrowserConformance => test_browser_conformance()?,
     };
     Ok(())
 }
@@ -101,8 +103,34 @@ impl khonsu_tools::code_coverage::Config for CodeCoverageConfig {
     }
 }
 
-fn generate_example_snapshots() -> Result<(), devx_cmd::Error> {
-    println!("Executing wasm-bindgen (cargo install wasm-bindgen if you don't have this)");
-    run!("cargo", "test", "--examples", "--all-features")?;
-    run!("cp", "-r", "target/snapshots", "gooey-canvas/examples/")
+fn generate_example_snapshots() -> anyhow::Result<()> {
+    println!("Executing cargo test with BLESS_SNAPSHOTS=1");
+    let status = std::process::Command::new("cargo")
+        .args(&["test", "--examples", "--all-features"])
+        .env("BLESS_SNAPSHOTS", "1")
+        .status()?;
+    anyhow::ensure!(status.success(), "cargo test failed");
+    Ok(())
+}
+
+fn test_browser_conformance() -> Result<(), devx_cmd::Error> {
+    println!(
+        "Executing cargo test (cargo install wasm-bindgen-cli, and install chromedriver or \
+         geckodriver to run headless)"
+    );
+    run!(
+        "cargo",
+        "test",
+        "--package",
+        "gooey-canvas",
+        "--test",
+        "browser_conformance",
+        "--no-default-features",
+        "--features",
+        "frontend-browser",
+        "--target",
+        "wasm32-unknown-unknown",
+        "--target-dir",
+        "target/wasm",
+    )
 }